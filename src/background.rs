@@ -5,7 +5,9 @@ use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
 use rand::{thread_rng, Rng};
 
-use crate::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::configuration::{Ruleset, LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::core_components::Persistent;
+use crate::currents::current_at;
 use crate::State;
 
 pub struct BackgroundPlugin;
@@ -14,7 +16,12 @@ impl Plugin for BackgroundPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ClearColor(Color::BLACK))
             .insert_resource(BubbleTimer::default())
-            .add_system_set(SystemSet::on_enter(State::Game).with_system(create_background))
+            .add_startup_system(create_background)
+            .add_system_set(
+                SystemSet::on_update(State::Menu)
+                    .with_system(move_bubbles)
+                    .with_system(despawn_bubbles),
+            )
             .add_system_set(
                 SystemSet::on_update(State::Game)
                     .with_system(spawn_bubbles)
@@ -35,7 +42,8 @@ fn create_background(mut commands: Commands, asset_server: Res<AssetServer>) {
             )),
             ..default()
         })
-        .insert(RenderLayers::layer(1));
+        .insert(RenderLayers::layer(1))
+        .insert(Persistent);
 }
 
 const BUBBLE_GROUP_TIMER_RANGE: Range<f32> = 1.5..6.0;
@@ -130,7 +138,11 @@ fn spawn_bubbles(
     }
 }
 
-fn move_bubbles(time: Res<Time>, mut bubbles: Query<(&mut Transform, &Wobble), With<Bubble>>) {
+fn move_bubbles(
+    time: Res<Time>,
+    ruleset: Res<Ruleset>,
+    mut bubbles: Query<(&mut Transform, &Wobble), With<Bubble>>,
+) {
     const BUBBLE_VELOCITY: f32 = 1.5;
     const BUBBLE_VELOCITY_SIZE_BONUS: f32 = 0.05;
 
@@ -142,6 +154,8 @@ fn move_bubbles(time: Res<Time>, mut bubbles: Query<(&mut Transform, &Wobble), W
             as f32
             * wobble.amplitude;
         transform.translation.y += BUBBLE_VELOCITY + size_bonus;
+        let current = current_at(&ruleset, transform.translation.truncate());
+        transform.translation += current.extend(0.0);
     }
 }
 
@@ -14,7 +14,10 @@ pub(super) struct AnimationState {
     breathing: Timer,
     swimming: Timer,
     pub(super) swim_speed: f32,
-    pub(super) chomping: Timer,
+    /// How quickly the bobbing and breathing cycles run.
+    pub(super) tempo: f32,
+    /// How far through a bite the jaws are, if biting at all.
+    pub(super) bite: Option<f32>,
 }
 
 impl Default for AnimationState {
@@ -24,7 +27,8 @@ impl Default for AnimationState {
             breathing: Timer::from_seconds(3.0, true),
             swimming: Timer::from_seconds(5.0, true),
             swim_speed: 1.0,
-            chomping: Timer::from_seconds(0.5, false),
+            tempo: 1.0,
+            bite: None,
         }
     }
 }
@@ -55,12 +59,58 @@ where
         .expect(&format!("could not find {:?}", part))
 }
 
+/// Poses the jaws partway through a bite, where the jaws are wide open at 0.9 and shut at 1.0.
+pub(super) fn chomp(
+    animation_state: Res<AnimationState>,
+    mut body_parts: Query<(&mut Transform, &BodyPart)>,
+) {
+    let t = match animation_state.bite {
+        Some(t) => t,
+        None => return,
+    };
+
+    let bottom_jaw = Animation::new([
+        AnimationStage::new(
+            0.0..0.9,
+            &|t| 1.0 - (1.0 - t) * (1.0 - t),
+            &|t, transform: &mut Transform| transform.translation.y -= 60.0 * t,
+        ),
+        AnimationStage::new(
+            0.9..1.0,
+            &|t| 1.0 - 2.0_f32.powf(10.0 * t - 10.0),
+            &|t, transform: &mut Transform| transform.translation.y -= 65.0 * t - 5.0,
+        ),
+    ]);
+
+    let (mut transform, _) = get_body_part(&mut body_parts, BodyPart::BottomJaw);
+    bottom_jaw.run(t, &mut transform);
+
+    let top_jaw = Animation::new([
+        AnimationStage::new(
+            0.0..0.9,
+            &|t| 1.0 - (1.0 - t) * (1.0 - t),
+            &|t, transform: &mut Transform| transform.translation.y += 90.0 * t,
+        ),
+        AnimationStage::new(
+            0.9..1.0,
+            &|t| 1.0 - 2.0_f32.powf(10.0 * t - 10.0),
+            &|t, transform: &mut Transform| transform.translation.y += 95.0 * t - 5.0,
+        ),
+    ]);
+
+    let (mut transform, _) = get_body_part(&mut body_parts, BodyPart::TopJaw);
+    top_jaw.run(t, &mut transform);
+}
+
 pub(super) fn breathe(
     mut animation_state: ResMut<AnimationState>,
     time: Res<Time>,
     mut body_parts: Query<(&mut Transform, &BodyPart)>,
 ) {
-    animation_state.breathing.tick(time.delta());
+    let tempo = animation_state.tempo;
+    animation_state
+        .breathing
+        .tick(Duration::from_secs_f32(time.delta_seconds() * tempo));
 
     let mut animate_mouth =
         |part, amp| {
@@ -85,7 +135,10 @@ pub(super) fn bob(
     time: Res<Time>,
     mut body_parts: Query<(&mut Transform, &BodyPart)>,
 ) {
-    animation_state.bobbing.tick(time.delta());
+    let tempo = animation_state.tempo;
+    animation_state
+        .bobbing
+        .tick(Duration::from_secs_f32(time.delta_seconds() * tempo));
 
     let animation = Animation::new([AnimationStage::new(
         0.0..1.0,
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::background::spawn_bubble_group;
+use crate::configuration::{BigFishIdle, HuntPrey, Ruleset, LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::core_components::{Dead, HitPoints, KilledBy, Originator, Projectile, Shielded};
+use crate::ink::{hidden_in_ink, InkCloud};
+use crate::player::{Player, PlayerConfiguration};
+
+use super::animation::AnimationState;
+use super::{
+    get_fish_space_scale, AttentionTarget, BigFish, EatList, ATTENTION_OFFSET, START_DEPTH,
+};
+
+/// How close, in fish space, the big fish must be to a corpse to start eating it.
+const EATING_DISTANCE: f32 = 0.5;
+/// How close, in world units, the jaws must be to the strike point to snap shut early.
+const STRIKE_DISTANCE: f32 = 20.0;
+/// How far from the middle of the jaws a player can be and still get bitten.
+const JAW_RADIUS: f32 = 150.0;
+/// How far through a bite the jaws are held open while stalking and lunging.
+const JAWS_OPEN: f32 = 0.89;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Behaviour {
+    /// Hovering beneath the weakest fish, waiting.
+    Lurking,
+    /// Closing in on a corpse, or rising and gaping at living prey.
+    Stalking,
+    /// Darting at the spot where the prey was when stalking ended.
+    Lunging,
+    Chomping,
+    /// Sinking back down after a meal or a miss.
+    Retreating,
+    /// Resting at the bottom, ignoring the living.
+    Sleeping,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Corpse(Entity),
+    Prey(Entity),
+}
+
+pub(super) struct BehaviourState {
+    pub(super) current: Behaviour,
+    timer: Timer,
+    target: Option<Target>,
+    strike_point: Vec3,
+    /// Where in the bite animation the current chomp started.
+    bite_from: f32,
+}
+
+impl Default for BehaviourState {
+    fn default() -> Self {
+        Self {
+            current: Behaviour::Lurking,
+            timer: Timer::default(),
+            target: None,
+            strike_point: Vec3::ZERO,
+            bite_from: 0.0,
+        }
+    }
+}
+
+impl BehaviourState {
+    fn enter(&mut self, behaviour: Behaviour, secs: f32) {
+        self.current = behaviour;
+        self.timer = Timer::from_seconds(secs, false);
+    }
+
+    /// How quickly the big fish closes the distance to its attention target.
+    pub(super) fn speed(&self) -> f32 {
+        match (self.current, self.target) {
+            (Behaviour::Lurking, _) => 0.0075,
+            (Behaviour::Stalking | Behaviour::Chomping, Some(Target::Corpse(_))) => 0.05,
+            (Behaviour::Stalking, _) => 0.03,
+            (Behaviour::Lunging | Behaviour::Chomping, _) => 0.15,
+            (Behaviour::Retreating, _) => 0.02,
+            (Behaviour::Sleeping, _) => 0.003,
+        }
+    }
+
+    fn tempo(&self) -> f32 {
+        match self.current {
+            Behaviour::Stalking | Behaviour::Lunging | Behaviour::Chomping => 2.0,
+            Behaviour::Sleeping => 0.3,
+            Behaviour::Lurking | Behaviour::Retreating => 1.0,
+        }
+    }
+}
+
+/// How long each living player has gone without shooting.
+#[derive(Default)]
+pub(super) struct Passivity(HashMap<Entity, f32>);
+
+pub(super) fn setup_behaviour(mut behaviour: ResMut<BehaviourState>, ruleset: Res<Ruleset>) {
+    behaviour.enter(Behaviour::Lurking, lurk_secs(&ruleset));
+}
+
+pub(super) fn track_passivity(
+    time: Res<Time>,
+    mut passivity: ResMut<Passivity>,
+    players: Query<Entity, (With<Player>, Without<Dead>)>,
+    shots: Query<&Originator, Added<Projectile>>,
+) {
+    passivity.0.retain(|p, _| players.get(*p).is_ok());
+
+    for player in players.iter() {
+        *passivity.0.entry(player).or_default() += time.delta_seconds();
+    }
+
+    for originator in shots.iter() {
+        passivity.0.insert(originator.0, 0.0);
+    }
+}
+
+fn lurk_secs(ruleset: &Ruleset) -> f32 {
+    ruleset.hunt().map(|h| h.interval_secs).unwrap_or(0.0)
+}
+
+/// Lurks or sleeps, as chosen by the given weights.
+fn idle(behaviour: &mut BehaviourState, ruleset: &Ruleset, weights: [u32; 2]) {
+    let idle = BigFishIdle::ALL
+        .choose_weighted(&mut thread_rng(), |i| weights[*i as usize])
+        .copied()
+        .unwrap_or(BigFishIdle::Lurking);
+
+    match idle {
+        BigFishIdle::Lurking => behaviour.enter(Behaviour::Lurking, lurk_secs(ruleset)),
+        BigFishIdle::Sleeping => behaviour.enter(Behaviour::Sleeping, ruleset.big_fish.sleep_secs),
+    }
+}
+
+/// Somewhere beneath the weakest fish, rising as they weaken.
+fn lurking_position(
+    player_configuration: &PlayerConfiguration,
+    hp_positions: &[(u32, Vec2)],
+) -> Vec3 {
+    let minimum_hp = hp_positions.iter().map(|(hp, _)| *hp).min();
+
+    if let Some(minimum_hp) = minimum_hp {
+        let starting_maximum_hp = player_configuration
+            .0
+            .iter()
+            .filter_map(|p| p.clone().map(|c| c.hp.0))
+            .max()
+            .unwrap();
+        let mut count = 0;
+        let mut sum = Vec2::ZERO;
+
+        for (_, position) in hp_positions.iter().filter(|(hp, _)| *hp == minimum_hp) {
+            count += 1;
+            sum += *position;
+        }
+
+        (sum / count as f32).extend(START_DEPTH * minimum_hp as f32 / starting_maximum_hp as f32)
+    } else {
+        Vec3::new(LOGICAL_WIDTH as f32, LOGICAL_HEIGHT as f32, START_DEPTH)
+    }
+}
+
+pub(super) fn update_behaviour(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    player_configuration: Res<PlayerConfiguration>,
+    passivity: Res<Passivity>,
+    mut behaviour: ResMut<BehaviourState>,
+    mut eat_list: ResMut<EatList>,
+    mut attention_target: ResMut<AttentionTarget>,
+    mut animation_state: ResMut<AnimationState>,
+    mut players: Query<
+        (
+            Entity,
+            &mut HitPoints,
+            &Transform,
+            Option<&Shielded>,
+            Option<&Dead>,
+        ),
+        With<Player>,
+    >,
+    big_fish: Query<(Entity, &Transform), With<BigFish>>,
+    clouds: Query<(&Transform, &InkCloud)>,
+) {
+    let rules = &ruleset.big_fish;
+
+    behaviour.timer.tick(time.delta());
+
+    let (fish_entity, fish_transform) = big_fish.single();
+    let mouth = fish_transform.translation - Vec3::Y * ATTENTION_OFFSET;
+
+    let target_position = match behaviour.target {
+        Some(Target::Corpse(e)) => players.get(e).ok().map(|(_, _, t, _, _)| t.translation),
+        // Prey that slips into ink is lost track of.
+        Some(Target::Prey(e)) => players
+            .get(e)
+            .ok()
+            .filter(|(_, _, _, _, dead)| dead.is_none())
+            .map(|(_, _, t, _, _)| t.translation)
+            .filter(|t| !rules.loses_prey_in_ink || !hidden_in_ink(t.truncate(), clouds.iter())),
+        None => None,
+    };
+
+    let next_corpse = eat_list.0.first().copied();
+    let drawn_to_corpse = next_corpse.is_some()
+        && match behaviour.current {
+            Behaviour::Lurking => true,
+            Behaviour::Retreating => rules.corpses_interrupt_retreat,
+            Behaviour::Sleeping => rules.corpses_wake,
+            _ => false,
+        };
+
+    match behaviour.current {
+        _ if drawn_to_corpse => {
+            behaviour.target = next_corpse.map(Target::Corpse);
+            behaviour.enter(Behaviour::Stalking, 0.0);
+        }
+        Behaviour::Lurking => {
+            let hp_positions = players
+                .iter()
+                .map(|(_, hp, t, _, _)| (hp.0, t.translation.truncate()))
+                .collect::<Vec<_>>();
+
+            attention_target.0 = lurking_position(&player_configuration, &hp_positions);
+
+            if let Some(hunt) = ruleset.hunt() {
+                if behaviour.timer.finished() {
+                    let living = players.iter().filter(|(_, _, t, _, dead)| {
+                        dead.is_none() && !hidden_in_ink(t.translation.truncate(), clouds.iter())
+                    });
+
+                    let chosen = match hunt.prey {
+                        HuntPrey::Weakest => living
+                            .min_by_key(|(_, hp, _, _, _)| hp.0)
+                            .map(|(p, _, _, _, _)| p),
+                        HuntPrey::MostPassive => living
+                            .map(|(p, _, _, _, _)| (p, passivity.0.get(&p).copied().unwrap_or(0.0)))
+                            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                            .map(|(p, _)| p),
+                    };
+
+                    if let Some(chosen) = chosen {
+                        behaviour.target = Some(Target::Prey(chosen));
+                        behaviour.enter(Behaviour::Stalking, rules.stalk_secs);
+                    } else {
+                        behaviour.enter(Behaviour::Lurking, hunt.interval_secs);
+                    }
+                }
+            }
+        }
+        Behaviour::Stalking => match (behaviour.target, target_position) {
+            (Some(Target::Corpse(_)), Some(position)) => {
+                attention_target.0 = position.truncate().extend(-0.1);
+
+                let scale = get_fish_space_scale();
+                let distance = (attention_target.0 + Vec3::Y * ATTENTION_OFFSET) * scale
+                    - fish_transform.translation * scale;
+
+                if distance.length() < EATING_DISTANCE {
+                    behaviour.bite_from = 0.0;
+                    behaviour.enter(Behaviour::Chomping, rules.chomp_secs);
+                }
+            }
+            (Some(Target::Prey(_)), Some(position)) => {
+                attention_target.0 = position.truncate().extend(START_DEPTH * 0.3);
+
+                if behaviour.timer.finished() {
+                    behaviour.strike_point = position.truncate().extend(-0.1);
+                    behaviour.enter(Behaviour::Lunging, rules.lunge_secs);
+                }
+            }
+            (target, _) => {
+                if let Some(Target::Corpse(corpse)) = target {
+                    eat_list.0.retain(|e| *e != corpse);
+                }
+
+                behaviour.target = None;
+                behaviour.enter(Behaviour::Retreating, rules.retreat_secs);
+            }
+        },
+        Behaviour::Lunging => {
+            attention_target.0 = behaviour.strike_point;
+
+            if behaviour.timer.finished()
+                || (mouth - behaviour.strike_point).truncate().length() < STRIKE_DISTANCE
+            {
+                behaviour.bite_from = 0.9;
+                behaviour.enter(Behaviour::Chomping, rules.chomp_secs * 0.1);
+            }
+        }
+        Behaviour::Chomping => {
+            if let Some(position) = target_position.filter(|_| behaviour.bite_from == 0.0) {
+                attention_target.0 = position.truncate().extend(-0.1);
+            }
+
+            if behaviour.timer.finished() {
+                match behaviour.target {
+                    Some(Target::Corpse(corpse)) => {
+                        commands.entity(corpse).despawn_recursive();
+                        eat_list.0.retain(|e| *e != corpse);
+
+                        spawn_bubble_group(
+                            &mut commands,
+                            &asset_server,
+                            attention_target.0,
+                            10,
+                            -50.0..50.0,
+                            -50.0..50.0,
+                            0.0..0.0001,
+                        );
+                    }
+                    Some(Target::Prey(_)) => {
+                        let jaws = mouth.truncate();
+                        let bite_damage = ruleset.hunt().and_then(|h| h.bite_damage);
+
+                        for (player, mut hp, transform, shielded, dead) in players.iter_mut() {
+                            if dead.is_some()
+                                || (transform.translation.truncate() - jaws).length() > JAW_RADIUS
+                            {
+                                continue;
+                            }
+
+                            if shielded.is_some() {
+                                spawn_bubble_group(
+                                    &mut commands,
+                                    &asset_server,
+                                    transform.translation,
+                                    8,
+                                    -60.0..60.0,
+                                    -60.0..60.0,
+                                    0.0..0.001,
+                                );
+                                continue;
+                            }
+
+                            hp.0 = match bite_damage {
+                                Some(damage) => hp.0.saturating_sub(damage),
+                                None => 0,
+                            };
+                            println!("Player {:?} hp: {}", player, hp.0);
+
+                            // The big fish is on nobody's team, so this scores for no one.
+                            if hp.0 == 0 {
+                                commands
+                                    .entity(player)
+                                    .insert(Dead)
+                                    .insert(KilledBy(fish_entity));
+                            }
+                        }
+                    }
+                    None => (),
+                }
+
+                behaviour.target = None;
+                behaviour.enter(Behaviour::Retreating, rules.retreat_secs);
+            }
+        }
+        Behaviour::Retreating => {
+            attention_target.0.z = START_DEPTH;
+
+            if behaviour.timer.finished() {
+                idle(&mut behaviour, &ruleset, rules.after_retreat_weights);
+            }
+        }
+        Behaviour::Sleeping => {
+            attention_target.0.z = START_DEPTH;
+
+            if behaviour.timer.finished() {
+                idle(&mut behaviour, &ruleset, rules.after_sleep_weights);
+            }
+        }
+    }
+
+    animation_state.tempo = behaviour.tempo();
+    animation_state.bite = match behaviour.current {
+        Behaviour::Stalking if matches!(behaviour.target, Some(Target::Prey(_))) => {
+            Some(JAWS_OPEN * behaviour.timer.percent())
+        }
+        Behaviour::Lunging => Some(JAWS_OPEN),
+        Behaviour::Chomping => {
+            Some(behaviour.bite_from + (1.0 - behaviour.bite_from) * behaviour.timer.percent())
+        }
+        _ => None,
+    };
+}
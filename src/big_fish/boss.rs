@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use rand::seq::IteratorRandom;
+use rand::thread_rng;
+
+use crate::collision::{CollisionLayer, CollisionLayers};
+use crate::configuration::Ruleset;
+use crate::core_components::{CollisionCircle, Dead, HitPoints};
+use crate::ink::{hidden_in_ink, InkCloud};
+use crate::match_flow::{MatchOver, Winner};
+use crate::player::{spawn_projectile, Player};
+
+use super::behaviour::{Behaviour, BehaviourState};
+use super::{get_closeness, BigFish, ATTENTION_OFFSET};
+
+/// How far from its middle the big fish can be hit, at full size.
+const BOSS_RADIUS: f32 = 300.0;
+/// Angle between neighbouring bubbles in a blast.
+const BLAST_SPREAD: f32 = 0.3;
+const BLAST_SCALE: f32 = 0.15;
+
+pub(super) struct BlastTimer(Timer);
+
+pub(super) fn arm_big_fish(
+    mut commands: Commands,
+    ruleset: Res<Ruleset>,
+    big_fish: Query<Entity, Added<BigFish>>,
+) {
+    let boss_fight = match &ruleset.boss_fight {
+        Some(boss_fight) => boss_fight,
+        None => return,
+    };
+
+    for big_fish in big_fish.iter() {
+        commands
+            .entity(big_fish)
+            .insert(HitPoints(boss_fight.hp))
+            .insert(CollisionCircle {
+                radius: BOSS_RADIUS,
+            })
+            .insert(
+                CollisionLayers::new(CollisionLayer::BigFish)
+                    .with_filter(CollisionLayer::Projectile),
+            );
+
+        commands.insert_resource(BlastTimer(Timer::from_seconds(
+            boss_fight.blast_interval_secs,
+            true,
+        )));
+    }
+}
+
+/// Only lets projectiles reach the big fish once it has come close enough to the surface.
+pub(super) fn expose_big_fish(
+    ruleset: Res<Ruleset>,
+    mut big_fish: Query<(&Transform, &mut CollisionCircle, &mut CollisionLayers), With<BigFish>>,
+) {
+    let boss_fight = match &ruleset.boss_fight {
+        Some(boss_fight) => boss_fight,
+        None => return,
+    };
+
+    for (transform, mut circle, mut layers) in big_fish.iter_mut() {
+        circle.radius = BOSS_RADIUS * transform.scale.x;
+
+        if get_closeness(transform) >= boss_fight.vulnerable_closeness {
+            layers.insert(CollisionLayer::BigFish);
+        } else {
+            layers.remove(CollisionLayer::BigFish);
+        }
+    }
+}
+
+pub(super) fn blast_bubbles(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    behaviour: Res<BehaviourState>,
+    blast_timer: Option<ResMut<BlastTimer>>,
+    big_fish: Query<(Entity, &Transform), (With<BigFish>, Without<Dead>)>,
+    players: Query<&Transform, (With<Player>, Without<Dead>)>,
+    clouds: Query<(&Transform, &InkCloud)>,
+) {
+    let (mut blast_timer, boss_fight) = match (blast_timer, &ruleset.boss_fight) {
+        (Some(blast_timer), Some(boss_fight)) => (blast_timer, boss_fight),
+        _ => return,
+    };
+
+    if !blast_timer.0.tick(time.delta()).just_finished() || behaviour.current == Behaviour::Sleeping
+    {
+        return;
+    }
+
+    let (big_fish, fish_transform) = match big_fish.get_single() {
+        Ok(big_fish) => big_fish,
+        Err(_) => return,
+    };
+
+    let mouth = (fish_transform.translation - Vec3::Y * ATTENTION_OFFSET).truncate();
+
+    let visible = players
+        .iter()
+        .filter(|t| !hidden_in_ink(t.translation.truncate(), clouds.iter()));
+
+    let aim = match visible.choose(&mut thread_rng()) {
+        Some(transform) => (transform.translation.truncate() - mouth)
+            .try_normalize()
+            .unwrap_or(-Vec2::Y),
+        None => return,
+    };
+
+    let middle = (boss_fight.blast_bubbles as f32 - 1.0) / 2.0;
+
+    for i in 0..boss_fight.blast_bubbles {
+        let rotation = Quat::from_rotation_z((i as f32 - middle) * BLAST_SPREAD);
+        let direction = (rotation * aim.extend(0.0)).truncate();
+
+        spawn_projectile(
+            &mut commands,
+            &asset_server,
+            &ruleset,
+            big_fish,
+            Transform::from_translation(mouth.extend(1.0)).with_scale(Vec3::splat(BLAST_SCALE)),
+            direction * boss_fight.blast_speed,
+            boss_fight.blast_damage,
+            Color::rgba(1.0, 1.0, 1.0, 0.8),
+            "images/bubble.png",
+        );
+    }
+}
+
+pub(super) fn check_boss_fight(
+    mut match_over: EventWriter<MatchOver>,
+    big_fish: Query<Option<&Dead>, (With<BigFish>, With<HitPoints>)>,
+    players: Query<(), (With<Player>, Without<Dead>)>,
+) {
+    let defeated = match big_fish.get_single() {
+        Ok(dead) => dead.is_some(),
+        Err(_) => return,
+    };
+
+    if defeated {
+        match_over.send(MatchOver(Winner::Players));
+    } else if players.is_empty() {
+        match_over.send(MatchOver(Winner::BigFish));
+    }
+}
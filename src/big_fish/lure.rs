@@ -0,0 +1,126 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::configuration::{LureEffect, Ruleset};
+use crate::core_components::{Dead, Velocity};
+use crate::energy_orbs::{spawn_fleeting_orb, OrbSize};
+use crate::player::Player;
+
+use super::behaviour::{Behaviour, BehaviourState};
+use super::model::BodyPart;
+use super::BigFish;
+
+const LURE_GLOW_SCALE: f32 = 1.5;
+const LURE_GLOW_COLOR: Color = Color::rgb(1.0, 1.0, 0.7);
+
+#[derive(Default)]
+pub(super) struct LureState {
+    timer: Timer,
+    glowing: bool,
+}
+
+/// A halo drawn over the lure in world space, since the big fish itself is only a dim reflection.
+#[derive(Component)]
+pub(super) struct LureGlow;
+
+pub(super) fn setup_lure(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    mut lure: ResMut<LureState>,
+) {
+    if let Some(rules) = &ruleset.big_fish_lure {
+        lure.timer = Timer::from_seconds(rules.interval_secs, false);
+        lure.glowing = false;
+    }
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("images/orb.png"),
+            transform: Transform::from_scale(Vec3::splat(LURE_GLOW_SCALE)),
+            sprite: Sprite {
+                color: LURE_GLOW_COLOR,
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(LureGlow);
+}
+
+pub(super) fn update_lure(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    behaviour: Res<BehaviourState>,
+    mut lure: ResMut<LureState>,
+    body_parts: Query<(&GlobalTransform, &BodyPart)>,
+    big_fish: Query<&Transform, With<BigFish>>,
+    mut glow: Query<
+        (&mut Transform, &mut Visibility, &mut Sprite),
+        (With<LureGlow>, Without<BigFish>),
+    >,
+    mut players: Query<
+        (&Transform, &mut Velocity),
+        (With<Player>, Without<Dead>, Without<LureGlow>),
+    >,
+) {
+    let rules = match &ruleset.big_fish_lure {
+        Some(rules) => rules,
+        None => return,
+    };
+
+    // The model is rendered at its own scale into the texture shown by the big fish sprite.
+    let fish_transform = big_fish.single();
+    let tip = body_parts
+        .iter()
+        .find(|(_, part)| **part == BodyPart::Lure)
+        .map(|(transform, _)| transform.translation.truncate())
+        .unwrap_or_default();
+    let tip = fish_transform.translation.truncate() + tip * fish_transform.scale.truncate();
+
+    if lure.timer.tick(time.delta()).finished() {
+        lure.glowing = !lure.glowing && behaviour.current != Behaviour::Sleeping;
+
+        if lure.glowing {
+            lure.timer = Timer::from_seconds(rules.glow_secs, false);
+
+            if let LureEffect::Bait { orb_lifetime } = rules.effect {
+                spawn_fleeting_orb(
+                    &mut commands,
+                    &asset_server,
+                    tip,
+                    OrbSize::Large,
+                    orb_lifetime,
+                );
+            }
+        } else {
+            lure.timer = Timer::from_seconds(rules.interval_secs, false);
+        }
+    }
+
+    let (mut glow_transform, mut visibility, mut sprite) = glow.single_mut();
+
+    visibility.is_visible = lure.glowing;
+    glow_transform.translation = tip.extend(0.4);
+    glow_transform.scale = Vec3::splat(LURE_GLOW_SCALE * fish_transform.scale.x);
+
+    if !lure.glowing {
+        return;
+    }
+
+    sprite.color.set_a((lure.timer.percent() * PI).sin() * 0.6);
+
+    if let LureEffect::Hypnotise { radius, strength } = rules.effect {
+        for (transform, mut velocity) in players.iter_mut() {
+            let offset = tip - transform.translation.truncate();
+            let distance = offset.length();
+
+            if distance > 0.0 && distance < radius {
+                velocity.0 += offset / distance * strength * (1.0 - distance / radius);
+            }
+        }
+    }
+}
@@ -1,20 +1,25 @@
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
 
-use crate::animation::{Animation, AnimationStage};
-use crate::background::spawn_bubble_group;
 use crate::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
-use crate::core_components::{Dead, HitPoints};
-use crate::player::PlayerConfiguration;
+use crate::core_components::Dead;
 use crate::render::additional_pass::AdditionalPassPlugin;
 use crate::State;
 
-use self::animation::{bob, breathe, get_body_part, reset_animation, swim, AnimationState};
+use self::animation::{bob, breathe, chomp, reset_animation, swim, AnimationState};
+use self::behaviour::{
+    setup_behaviour, track_passivity, update_behaviour, BehaviourState, Passivity,
+};
+use self::boss::{arm_big_fish, blast_bubbles, check_boss_fight, expose_big_fish};
 use self::camera::{setup_camera, BigFishCamera};
-use self::model::{build_model, BodyPart};
+use self::lure::{setup_lure, update_lure, LureState};
+use self::model::build_model;
 
 mod animation;
+mod behaviour;
+mod boss;
 mod camera;
+mod lure;
 mod model;
 
 pub struct BigFishPlugin;
@@ -24,6 +29,9 @@ impl Plugin for BigFishPlugin {
         app.insert_resource(AnimationState::default())
             .insert_resource(AttentionTarget::default())
             .insert_resource(EatList::default())
+            .insert_resource(BehaviourState::default())
+            .insert_resource(Passivity::default())
+            .insert_resource(LureState::default())
             .add_plugin(AdditionalPassPlugin::<BigFishCamera>::new(
                 "big_fish_pass",
                 Some("foreground_pass"),
@@ -31,16 +39,24 @@ impl Plugin for BigFishPlugin {
             .add_system_set(
                 SystemSet::on_enter(State::Game)
                     .with_system(setup_camera)
-                    .with_system(build_model),
+                    .with_system(build_model)
+                    .with_system(setup_behaviour)
+                    .with_system(setup_lure),
             )
             .add_system_set(
                 SystemSet::on_update(State::Game)
                     .with_system(reset_animation.before("big_fish_animation"))
                     .with_system(create_depth)
                     .with_system(add_dead_things_to_menu)
-                    .with_system(update_attention_target)
-                    .with_system(follow_attention_target)
-                    .with_system(eat_dead_things.after("big_fish_animation")),
+                    .with_system(track_passivity)
+                    .with_system(update_behaviour.label("update_behaviour"))
+                    .with_system(follow_attention_target.after("update_behaviour"))
+                    .with_system(update_lure.after("update_behaviour").before("move_players"))
+                    .with_system(arm_big_fish)
+                    .with_system(expose_big_fish.before("collision_detection"))
+                    .with_system(blast_bubbles.after("update_behaviour"))
+                    .with_system(check_boss_fight.label("match_conditions"))
+                    .with_system(chomp.after("big_fish_animation")),
             )
             .add_system_set(
                 SystemSet::on_update(State::Game)
@@ -68,47 +84,6 @@ struct AttentionTarget(Vec3);
 #[derive(Default)]
 struct EatList(Vec<Entity>);
 
-fn update_attention_target(
-    mut attention_target: ResMut<AttentionTarget>,
-    eat_list: Res<EatList>,
-    player_configuration: Res<PlayerConfiguration>,
-    hp_entities: Query<(&HitPoints, &Transform)>,
-) {
-    if let Some(eat_target) = eat_list.0.first() {
-        let (_, transform) = hp_entities
-            .get(*eat_target)
-            .expect("could not find eat target");
-
-        attention_target.0 = transform.translation;
-        attention_target.0.z = -0.1;
-
-        return;
-    }
-
-    let minimum_hp = hp_entities.iter().min_by_key(|(hp, _)| hp.0);
-
-    if let Some((&HitPoints(minimum_hp), _)) = minimum_hp {
-        let starting_maximum_hp = player_configuration
-            .0
-            .iter()
-            .filter_map(|p| p.clone().map(|c| c.hp.0))
-            .max()
-            .unwrap();
-        let mut count = 0;
-        let mut sum = Vec2::ZERO;
-
-        for (_, transform) in hp_entities.iter().filter(|(hp, _)| hp.0 == minimum_hp) {
-            count += 1;
-            sum += transform.translation.truncate();
-        }
-
-        attention_target.0 = (sum / count as f32)
-            .extend(START_DEPTH * minimum_hp as f32 / starting_maximum_hp as f32);
-    } else {
-        attention_target.0 = Vec3::new(LOGICAL_WIDTH as f32, LOGICAL_HEIGHT as f32, START_DEPTH);
-    }
-}
-
 fn get_fish_space_scale() -> Vec3 {
     let min_dim = LOGICAL_WIDTH.min(LOGICAL_HEIGHT) as f32;
     Vec3::new(1.0 / min_dim, 1.0 / min_dim, 1.0 / -START_DEPTH)
@@ -116,11 +91,11 @@ fn get_fish_space_scale() -> Vec3 {
 
 fn follow_attention_target(
     mut animation_state: ResMut<AnimationState>,
-    eat_list: Res<EatList>,
+    behaviour: Res<BehaviourState>,
     attention_target: Res<AttentionTarget>,
     mut big_fish: Query<&mut Transform, With<BigFish>>,
 ) {
-    let speed = if !eat_list.0.is_empty() { 0.05 } else { 0.0075 };
+    let speed = behaviour.speed();
 
     let mut transform = big_fish.single_mut();
 
@@ -135,94 +110,23 @@ fn follow_attention_target(
     transform.translation += distance * speed / scale;
 }
 
+/// How near the big fish is to the surface, from 0 at its starting depth to 1 at the surface.
+fn get_closeness(transform: &Transform) -> f32 {
+    1.0 - transform.translation.z / START_DEPTH
+}
+
 fn create_depth(mut big_fish: Query<(&mut Sprite, &mut Transform), With<BigFish>>) {
     let (mut sprite, mut transform) = big_fish.single_mut();
 
-    let closeness = 1.0 - transform.translation.z / START_DEPTH;
+    let closeness = get_closeness(&transform);
 
     sprite.color.set_a(closeness * 0.6);
     transform.scale = Vec3::splat(closeness * 0.4 + 0.6);
 }
 
-fn add_dead_things_to_menu(mut eat_list: ResMut<EatList>, dead_things: Query<Entity, Added<Dead>>) {
-    eat_list.0.extend(dead_things.iter());
-}
-
-fn eat_dead_things(
-    mut commands: Commands,
-    mut animation_state: ResMut<AnimationState>,
+fn add_dead_things_to_menu(
     mut eat_list: ResMut<EatList>,
-    asset_server: Res<AssetServer>,
-    attention_target: Res<AttentionTarget>,
-    time: Res<Time>,
-    mut hideables: Query<&mut Visibility>,
-    mut body_parts: Query<(&mut Transform, &BodyPart), Without<BigFish>>,
-    big_fish: Query<&Transform, With<BigFish>>,
+    dead_things: Query<Entity, (Added<Dead>, Without<BigFish>)>,
 ) {
-    if let Some(eat_target) = eat_list.0.first() {
-        let transform = big_fish.single();
-
-        let scale = get_fish_space_scale();
-
-        let target = (attention_target.0 + Vec3::Y * ATTENTION_OFFSET) * scale;
-        let pos = transform.translation * scale;
-
-        let distance = target - pos;
-
-        if distance.length() < 0.5 {
-            animation_state.chomping.tick(time.delta());
-            let t = animation_state.chomping.percent();
-
-            let bottom_jaw = Animation::new([
-                AnimationStage::new(
-                    0.0..0.9,
-                    &|t| 1.0 - (1.0 - t) * (1.0 - t),
-                    &|t, transform: &mut Transform| transform.translation.y -= 60.0 * t,
-                ),
-                AnimationStage::new(
-                    0.9..1.0,
-                    &|t| 1.0 - 2.0_f32.powf(10.0 * t - 10.0),
-                    &|t, transform: &mut Transform| transform.translation.y -= 65.0 * t - 5.0,
-                ),
-            ]);
-
-            let (mut transform, _) = get_body_part(&mut body_parts, BodyPart::BottomJaw);
-            bottom_jaw.run(t, &mut transform);
-
-            let top_jaw = Animation::new([
-                AnimationStage::new(
-                    0.0..0.9,
-                    &|t| 1.0 - (1.0 - t) * (1.0 - t),
-                    &|t, transform: &mut Transform| transform.translation.y += 90.0 * t,
-                ),
-                AnimationStage::new(
-                    0.9..1.0,
-                    &|t| 1.0 - 2.0_f32.powf(10.0 * t - 10.0),
-                    &|t, transform: &mut Transform| transform.translation.y += 95.0 * t - 5.0,
-                ),
-            ]);
-
-            let (mut transform, _) = get_body_part(&mut body_parts, BodyPart::TopJaw);
-            top_jaw.run(t, &mut transform);
-
-            let mut visibility = hideables.get_mut(*eat_target).expect("can't find hideable");
-
-            if animation_state.chomping.just_finished() {
-                visibility.is_visible = false;
-                animation_state.chomping.reset();
-                commands.entity(*eat_target).despawn_recursive();
-                eat_list.0.remove(0);
-
-                spawn_bubble_group(
-                    &mut commands,
-                    &asset_server,
-                    attention_target.0,
-                    10,
-                    -50.0..50.0,
-                    -50.0..50.0,
-                    0.0..0.0001,
-                );
-            }
-        }
-    }
+    eat_list.0.extend(dead_things.iter());
 }
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::core_components::CollisionCircle;
+use crate::State;
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Contacts::default())
+            .insert_resource(ArenaBounds::default())
+            .add_event::<CollisionStarted>()
+            .add_event::<CollisionEnded>()
+            .add_system_set(SystemSet::on_exit(State::Game).with_system(clear_contacts))
+            .add_system_set(
+                SystemSet::on_update(State::Game).with_system(
+                    detect_collisions
+                        .label("collision_detection")
+                        .after("physics"),
+                ),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CollisionLayer {
+    Player,
+    Projectile,
+    Orb,
+    Pickup,
+    Shield,
+    BigFish,
+}
+
+impl CollisionLayer {
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Which layers an entity belongs to, and which layers it wants to collide with.
+/// Two entities only collide if each one's filters accept the other's memberships.
+#[derive(Clone, Component, Copy, Debug)]
+pub struct CollisionLayers {
+    memberships: u32,
+    filters: u32,
+}
+
+impl CollisionLayers {
+    pub const fn new(layer: CollisionLayer) -> Self {
+        Self {
+            memberships: layer.bit(),
+            filters: 0,
+        }
+    }
+
+    pub const fn with_filter(mut self, layer: CollisionLayer) -> Self {
+        self.filters |= layer.bit();
+        self
+    }
+
+    pub fn contains(&self, layer: CollisionLayer) -> bool {
+        self.memberships & layer.bit() != 0
+    }
+
+    pub fn insert(&mut self, layer: CollisionLayer) {
+        self.memberships |= layer.bit();
+    }
+
+    pub fn remove(&mut self, layer: CollisionLayer) {
+        self.memberships &= !layer.bit();
+    }
+
+    pub fn interacts_with(&self, other: &CollisionLayers) -> bool {
+        self.filters & other.memberships != 0 && other.filters & self.memberships != 0
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionStarted(pub Entity, pub Entity);
+
+impl CollisionStarted {
+    /// Orders the pair so that the first entity is the one matching `first`.
+    pub fn ordered(&self, first: impl Fn(Entity) -> bool) -> Option<(Entity, Entity)> {
+        order_pair((self.0, self.1), first)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionEnded(pub Entity, pub Entity);
+
+impl CollisionEnded {
+    /// Orders the pair so that the first entity is the one matching `first`.
+    pub fn ordered(&self, first: impl Fn(Entity) -> bool) -> Option<(Entity, Entity)> {
+        order_pair((self.0, self.1), first)
+    }
+}
+
+fn order_pair(pair: (Entity, Entity), first: impl Fn(Entity) -> bool) -> Option<(Entity, Entity)> {
+    if first(pair.0) {
+        Some(pair)
+    } else if first(pair.1) {
+        Some((pair.1, pair.0))
+    } else {
+        None
+    }
+}
+
+/// Every pair of entities that overlapped during the last detection pass.
+#[derive(Default)]
+pub struct Contacts(HashSet<(Entity, Entity)>);
+
+impl Contacts {
+    pub fn contains(&self, a: Entity, b: Entity) -> bool {
+        self.0.contains(&(a.min(b), a.max(b)))
+    }
+
+    pub fn colliding_with(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().filter_map(move |&(a, b)| {
+            if a == entity {
+                Some(b)
+            } else if b == entity {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// The part of the arena players are currently allowed in.
+pub struct ArenaBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Default for ArenaBounds {
+    fn default() -> Self {
+        Self {
+            min: Vec2::ZERO,
+            max: Vec2::new(LOGICAL_WIDTH as f32, LOGICAL_HEIGHT as f32),
+        }
+    }
+}
+
+/// The distance between the edges of two circles.  Negative when they overlap.
+pub fn separation(
+    position_a: Vec2,
+    circle_a: &CollisionCircle,
+    position_b: Vec2,
+    circle_b: &CollisionCircle,
+) -> f32 {
+    (position_b - position_a).length() - circle_a.radius - circle_b.radius
+}
+
+/// Picks a random spot within `bounds` where a circle of `radius` won't touch any of the given
+/// `(position, radius)` obstacles, giving up after a few tries.
+pub fn random_clear_position(
+    bounds: &ArenaBounds,
+    radius: f32,
+    obstacles: &[(Vec2, f32)],
+) -> Option<Vec2> {
+    const ARENA_MARGIN: f32 = 100.0;
+    const ATTEMPTS: usize = 10;
+
+    // Keep away from the walls, though not so far that a shrunken arena has no room left.
+    let margin = Vec2::splat(ARENA_MARGIN).min((bounds.max - bounds.min) / 4.0);
+    let (min, max) = (bounds.min + margin, bounds.max - margin);
+
+    if min.x >= max.x || min.y >= max.y {
+        return None;
+    }
+
+    let mut rng = thread_rng();
+
+    (0..ATTEMPTS)
+        .map(|_| Vec2::new(rng.gen_range(min.x..max.x), rng.gen_range(min.y..max.y)))
+        .find(|p| {
+            obstacles
+                .iter()
+                .all(|(o, r)| (*o - *p).length() > radius + r)
+        })
+}
+
+fn clear_contacts(mut contacts: ResMut<Contacts>) {
+    contacts.0.clear();
+}
+
+fn detect_collisions(
+    mut contacts: ResMut<Contacts>,
+    mut started: EventWriter<CollisionStarted>,
+    mut ended: EventWriter<CollisionEnded>,
+    colliders: Query<(Entity, &Transform, &CollisionCircle, &CollisionLayers)>,
+) {
+    let mut current = HashSet::new();
+
+    for [(a, transform_a, circle_a, layers_a), (b, transform_b, circle_b, layers_b)] in
+        colliders.iter_combinations()
+    {
+        if layers_a.interacts_with(layers_b)
+            && separation(
+                transform_a.translation.truncate(),
+                circle_a,
+                transform_b.translation.truncate(),
+                circle_b,
+            ) <= 0.0
+        {
+            current.insert((a.min(b), a.max(b)));
+        }
+    }
+
+    for &(a, b) in current.difference(&contacts.0) {
+        started.send(CollisionStarted(a, b));
+    }
+
+    for &(a, b) in contacts.0.difference(&current) {
+        ended.send(CollisionEnded(a, b));
+    }
+
+    contacts.0 = current;
+}
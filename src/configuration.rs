@@ -18,6 +18,7 @@ impl Plugin for ConfigurationPlugin {
         };
 
         app.insert_resource(window_configuration)
+            .insert_resource(Ruleset::default())
             .add_system(adjust_projections);
     }
 }
@@ -26,6 +27,323 @@ pub const LOGICAL_WIDTH: u32 = 1920;
 pub const LOGICAL_HEIGHT: u32 = 1080;
 pub const LOGICAL_ASPECT: f32 = 16.0 / 9.0;
 
+/// Gameplay rules that can be changed between matches.
+#[derive(Clone)]
+pub struct Ruleset {
+    pub game_mode: GameMode,
+    /// Seconds before a projectile that hasn't hit anything is removed.
+    pub projectile_lifetime: f32,
+    /// How many times a projectile bounces off the arena walls before it is removed.
+    pub projectile_ricochets: u32,
+    pub orb_placement: OrbPlacement,
+    /// How many orbs are in play for each player.  If unset, there's one at every fixed position.
+    pub orbs_per_player: Option<usize>,
+    pub orb_respawn_secs: f32,
+    /// Relative chances of a small, medium or large orb appearing.
+    pub orb_size_weights: [u32; 3],
+    pub feeding_frenzy: Option<FeedingFrenzy>,
+    pub big_fish: BigFishBehaviour,
+    /// Lets the big fish hunt living players instead of only eating the dead.
+    pub big_fish_hunt: Option<BigFishHunt>,
+    /// Makes the big fish's lure glow now and then, tempting players closer.
+    pub big_fish_lure: Option<BigFishLure>,
+    /// Turns the match into everyone against the big fish.
+    pub boss_fight: Option<BossFight>,
+    /// Whether projectiles can hurt the shooter's teammates.
+    pub friendly_fire: bool,
+    /// Splits the energy from each orb evenly between the collector and their living teammates.
+    pub team_orb_sharing: bool,
+    pub sudden_death: Option<SuddenDeath>,
+    /// How bouncy fish are when they bump into each other, from 0 for not at all to 1 for
+    /// perfectly elastic.
+    pub collision_restitution: f32,
+    /// Flows in the water that carry fish, projectiles and bubbles along.
+    pub currents: Vec<Current>,
+    pub size_stats: SizeStats,
+    /// Lets fish that have grown much bigger than others take bites out of them.
+    pub predation: Option<Predation>,
+    pub shield_model: ShieldModel,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self {
+            game_mode: GameMode::LastFishStanding,
+            projectile_lifetime: 5.0,
+            projectile_ricochets: 0,
+            orb_placement: OrbPlacement::Fixed,
+            orbs_per_player: None,
+            orb_respawn_secs: 15.0,
+            orb_size_weights: [0, 1, 0],
+            feeding_frenzy: None,
+            big_fish: BigFishBehaviour::default(),
+            big_fish_hunt: None,
+            big_fish_lure: None,
+            boss_fight: None,
+            friendly_fire: false,
+            team_orb_sharing: false,
+            sudden_death: None,
+            collision_restitution: 0.5,
+            currents: Vec::new(),
+            size_stats: SizeStats::default(),
+            predation: None,
+            shield_model: ShieldModel::Drain,
+        }
+    }
+}
+
+impl Ruleset {
+    /// How the big fish hunts the living, if it does.  A boss fight brings its own hunt.
+    pub fn hunt(&self) -> Option<&BigFishHunt> {
+        self.boss_fight
+            .as_ref()
+            .map(|b| &b.hunt)
+            .or(self.big_fish_hunt.as_ref())
+    }
+}
+
+/// What the teams are playing for, and when the match ends.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameMode {
+    /// The last team with a fish still alive wins.
+    LastFishStanding,
+    /// Teams score for every second they have a fish inside a zone that wanders the arena.
+    KingOfTheHill {
+        target_score: f32,
+        zone_radius: f32,
+        zone_speed: f32,
+    },
+    /// The team that has eaten the most energy when time runs out wins.
+    OrbHoarder { time_limit_secs: f32 },
+    /// The team with the most kills when time runs out wins.  Dead fish come back after a while.
+    TimedDeathmatch {
+        time_limit_secs: f32,
+        respawn_secs: f32,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OrbPlacement {
+    /// Orbs always reappear at the same fixed spots.
+    Fixed,
+    /// Orbs reappear somewhere random, away from any players.
+    Random,
+}
+
+/// Periodic bursts of short-lived orbs clustered around one spot.
+#[derive(Clone)]
+pub struct FeedingFrenzy {
+    pub interval_secs: f32,
+    pub orb_count: usize,
+    /// How far from the burst's center its orbs can land.
+    pub spread: f32,
+    /// Seconds before an uneaten frenzy orb disappears.
+    pub orb_lifetime: f32,
+}
+
+/// How long the big fish stays in each of its behaviours, and where it goes next.
+#[derive(Clone)]
+pub struct BigFishBehaviour {
+    /// Seconds spent rising and gaping at living prey before lunging.
+    pub stalk_secs: f32,
+    /// Longest a lunge can last before the jaws snap shut regardless.
+    pub lunge_secs: f32,
+    /// Seconds a full bite takes, from jaws wide open to shut.
+    pub chomp_secs: f32,
+    pub retreat_secs: f32,
+    pub sleep_secs: f32,
+    /// Relative chances of lurking or dozing off once it's done retreating.
+    pub after_retreat_weights: [u32; 2],
+    /// Relative chances of lurking or dozing on once it wakes up.
+    pub after_sleep_weights: [u32; 2],
+    /// Whether a fresh corpse draws it away mid-retreat rather than once it's back down.
+    pub corpses_interrupt_retreat: bool,
+    /// Whether a fresh corpse wakes it up rather than waiting for it to wake by itself.
+    pub corpses_wake: bool,
+    /// Whether prey that slips into ink makes it give up the hunt.
+    pub loses_prey_in_ink: bool,
+}
+
+impl Default for BigFishBehaviour {
+    fn default() -> Self {
+        Self {
+            stalk_secs: 2.0,
+            lunge_secs: 1.0,
+            chomp_secs: 0.5,
+            retreat_secs: 3.0,
+            sleep_secs: 8.0,
+            after_retreat_weights: [4, 1],
+            after_sleep_weights: [1, 0],
+            corpses_interrupt_retreat: true,
+            corpses_wake: true,
+            loses_prey_in_ink: true,
+        }
+    }
+}
+
+/// What the big fish does when it has nothing better to do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BigFishIdle {
+    Lurking,
+    Sleeping,
+}
+
+impl BigFishIdle {
+    pub const ALL: [BigFishIdle; 2] = [BigFishIdle::Lurking, BigFishIdle::Sleeping];
+}
+
+#[derive(Clone)]
+pub struct BigFishHunt {
+    /// Seconds spent lurking between hunts.
+    pub interval_secs: f32,
+    pub prey: HuntPrey,
+    /// Hit points taken from anyone caught in the jaws.  If unset, they're eaten outright.
+    pub bite_damage: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HuntPrey {
+    /// The player with the fewest hit points.
+    Weakest,
+    /// The player who has gone the longest without shooting.
+    MostPassive,
+}
+
+#[derive(Clone)]
+pub struct BigFishLure {
+    /// Seconds the lure stays dark between glows.
+    pub interval_secs: f32,
+    pub glow_secs: f32,
+    pub effect: LureEffect,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LureEffect {
+    /// Drags nearby players towards the lure, more strongly the closer they are.
+    Hypnotise { radius: f32, strength: f32 },
+    /// Leaves a large, short-lived orb at the tip of the lure.
+    Bait { orb_lifetime: f32 },
+}
+
+#[derive(Clone)]
+pub struct BossFight {
+    pub hp: u32,
+    /// How close to the surface the big fish must be to get hurt, from 0 at its starting depth
+    /// to 1 at the surface.
+    pub vulnerable_closeness: f32,
+    pub hunt: BigFishHunt,
+    pub blast_interval_secs: f32,
+    /// How many bubbles are fired in each blast, fanned out around the aim.
+    pub blast_bubbles: usize,
+    pub blast_speed: f32,
+    pub blast_damage: u32,
+}
+
+/// Closes the arena in around the players once a match has dragged on.
+#[derive(Clone)]
+pub struct SuddenDeath {
+    /// Seconds into the match before the walls start to close in.
+    pub start_secs: f32,
+    /// Seconds the walls take to close in as far as they'll go.
+    pub shrink_secs: f32,
+    /// How much of the arena's width and height is left at the end.
+    pub final_size: f32,
+}
+
+/// A flow in the water.  Speeds are in pixels per frame, like `Velocity`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Current {
+    /// A steady flow through a rectangular stretch of the arena.
+    Stream {
+        min: Vec2,
+        max: Vec2,
+        velocity: Vec2,
+    },
+    /// Water swirling around a point, fastest at the center.  Positive speeds swirl
+    /// anticlockwise.
+    Vortex {
+        center: Vec2,
+        radius: f32,
+        speed: f32,
+    },
+    /// Water rising from a point, fastest at the center.  Negative speeds make a downwelling.
+    Upwelling {
+        center: Vec2,
+        radius: f32,
+        speed: f32,
+    },
+}
+
+/// How a fish's stats change as it eats and grows.  Each stat is scaled by the fish's size,
+/// relative to a fresh one, raised to its exponent, so positive exponents grow with the fish
+/// and negative ones shrink.
+#[derive(Clone)]
+pub struct SizeStats {
+    /// How much bigger each unit of energy makes a fish.
+    pub growth_per_energy: f32,
+    /// How hard the fish is to shove about in collisions.
+    pub mass_exponent: f32,
+    pub damage_exponent: f32,
+    pub acceleration_exponent: f32,
+    pub turning_exponent: f32,
+}
+
+impl Default for SizeStats {
+    fn default() -> Self {
+        Self {
+            growth_per_energy: 1.06,
+            mass_exponent: 2.0,
+            damage_exponent: 1.0,
+            acceleration_exponent: -0.5,
+            turning_exponent: -1.0,
+        }
+    }
+}
+
+impl SizeStats {
+    /// How big a fish with this much energy grows, relative to a fresh one.
+    pub fn size(&self, energy: f32) -> f32 {
+        self.growth_per_energy.powf(energy)
+    }
+
+    pub fn mass(&self, size: f32) -> f32 {
+        size.powf(self.mass_exponent)
+    }
+
+    pub fn damage(&self, size: f32) -> f32 {
+        size.powf(self.damage_exponent)
+    }
+
+    pub fn acceleration(&self, size: f32) -> f32 {
+        size.powf(self.acceleration_exponent)
+    }
+
+    pub fn turning(&self, size: f32) -> f32 {
+        size.powf(self.turning_exponent)
+    }
+}
+
+#[derive(Clone)]
+pub struct Predation {
+    /// How many times bigger than another a fish must be to bite it.
+    pub size_ratio: f32,
+    pub bite_damage: u32,
+    /// Most energy a bite takes from the victim and gives to the biter.
+    pub energy_stolen: f32,
+    /// Seconds a fish has to wait after biting before it can bite again.
+    pub bite_cooldown_secs: f32,
+}
+
+/// What keeping a shield up costs.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ShieldModel {
+    /// Energy drains away steadily for as long as the shield is held, whatever hits it.
+    Drain,
+    /// Holding the shield is free, but every projectile it blocks costs energy and cracks it a
+    /// little more.  Once it has taken `hits` blocks it bursts.
+    Durability { block_cost: f32, hits: u32 },
+}
+
 fn adjust_projections(
     mut query: Query<&mut OrthographicProjection, With<MainCamera>>,
     windows: Res<Windows>,
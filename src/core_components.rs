@@ -8,12 +8,19 @@ pub struct CollisionCircle {
     pub radius: f32,
 }
 
+#[derive(Clone, Component)]
+pub struct Damage(pub u32);
+
 #[derive(Clone, Component)]
 pub struct Dead;
 
 #[derive(Clone, Component, Default)]
 pub struct Energy(pub f32);
 
+/// Who landed the killing blow on something that's now `Dead`.
+#[derive(Clone, Component)]
+pub struct KilledBy(pub Entity);
+
 #[derive(Clone, Component)]
 pub struct HitPoints(pub u32);
 
@@ -26,6 +33,10 @@ pub struct Originator(pub Entity);
 #[derive(Clone, Component)]
 pub struct Projectile;
 
+/// Kept when the arena is cleared between matches, along with the cameras.
+#[derive(Clone, Component)]
+pub struct Persistent;
+
 #[derive(Clone, Component)]
 pub struct Shield;
 
@@ -0,0 +1,147 @@
+use std::f32::consts::PI;
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use rand::{thread_rng, Rng};
+
+use crate::configuration::{Current, Ruleset};
+use crate::State;
+
+pub struct CurrentsPlugin;
+
+impl Plugin for CurrentsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticleTimer(Timer::from_seconds(
+            PARTICLE_INTERVAL_SECS,
+            true,
+        )))
+        .add_system_set(
+            SystemSet::on_update(State::Game)
+                .with_system(spawn_particles)
+                .with_system(move_particles),
+        );
+    }
+}
+
+const PARTICLE_INTERVAL_SECS: f32 = 0.1;
+const PARTICLE_LIFETIME_RANGE: Range<f32> = 1.5..3.0;
+const PARTICLE_SIZE_RANGE: Range<f32> = 2.0..5.0;
+const PARTICLE_ALPHA: f32 = 0.3;
+
+struct ParticleTimer(Timer);
+
+/// A speck drifting along with a current, to show which way the water flows.
+#[derive(Component)]
+struct Particle(Timer);
+
+/// How fast the water flows at a point, with every current in the arena added up.
+pub fn current_at(ruleset: &Ruleset, position: Vec2) -> Vec2 {
+    ruleset
+        .currents
+        .iter()
+        .map(|current| match *current {
+            Current::Stream { min, max, velocity } => {
+                if position.cmpge(min).all() && position.cmple(max).all() {
+                    velocity
+                } else {
+                    Vec2::ZERO
+                }
+            }
+            Current::Vortex {
+                center,
+                radius,
+                speed,
+            } => {
+                let offset = position - center;
+                let falloff = (1.0 - offset.length() / radius).max(0.0);
+
+                offset.perp().normalize_or_zero() * speed * falloff
+            }
+            Current::Upwelling {
+                center,
+                radius,
+                speed,
+            } => {
+                let falloff = (1.0 - (position - center).length() / radius).max(0.0);
+
+                Vec2::Y * speed * falloff
+            }
+        })
+        .fold(Vec2::ZERO, |total, current| total + current)
+}
+
+fn spawn_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    mut timer: ResMut<ParticleTimer>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut rng = thread_rng();
+
+    for current in ruleset.currents.iter() {
+        let position = match *current {
+            // Currents with no area to them have nowhere to show.
+            Current::Stream { min, max, .. } if min.x >= max.x || min.y >= max.y => continue,
+            Current::Vortex { radius, .. } | Current::Upwelling { radius, .. } if radius <= 0.0 => {
+                continue
+            }
+            Current::Stream { min, max, .. } => {
+                Vec2::new(rng.gen_range(min.x..max.x), rng.gen_range(min.y..max.y))
+            }
+            Current::Vortex { center, radius, .. } | Current::Upwelling { center, radius, .. } => {
+                let angle = rng.gen_range(-PI..PI);
+                center + Vec2::new(angle.cos(), angle.sin()) * rng.gen_range(0.0..radius)
+            }
+        };
+
+        // The orb texture is 128 pixels across.
+        let size = rng.gen_range(PARTICLE_SIZE_RANGE) / 128.0;
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load("images/orb.png"),
+                // Drawn with the background, just in front of it and behind the fish.
+                transform: Transform::from_translation(position.extend(-0.5))
+                    .with_scale(Vec3::splat(size)),
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 1.0, 1.0, 0.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(RenderLayers::layer(1))
+            .insert(Particle(Timer::from_seconds(
+                rng.gen_range(PARTICLE_LIFETIME_RANGE),
+                false,
+            )));
+    }
+}
+
+fn move_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    ruleset: Res<Ruleset>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        if particle.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let current = current_at(&ruleset, transform.translation.truncate());
+        transform.translation += current.extend(0.0);
+
+        // Fade in and back out again over the particle's life.
+        let t = particle.0.percent();
+        sprite
+            .color
+            .set_a(PARTICLE_ALPHA * (1.0 - (2.0 * t - 1.0).abs()));
+    }
+}
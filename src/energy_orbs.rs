@@ -1,22 +1,43 @@
 use std::time::Duration;
 
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
 
-use crate::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
-use crate::core_components::{CollisionCircle, Energy, Shielded};
-use crate::player::{Player, PLAYER_SCALE, PLAYER_SHIELD_SCALE};
+use crate::background::spawn_bubble_group;
+use crate::collision::{
+    random_clear_position, ArenaBounds, CollisionLayer, CollisionLayers, Contacts,
+};
+use crate::configuration::{OrbPlacement, Ruleset, LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::core_components::{CollisionCircle, Dead, Energy, Shielded};
+use crate::player::{
+    Inflated, Magnet, Player, PlayerConfiguration, Species, Team, PLAYER_SCALE, PLAYER_SHIELD_SCALE,
+};
 use crate::State;
 
 pub struct EnergyOrbsPlugin;
 
 impl Plugin for EnergyOrbsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(State::Game).with_system(spawn_starting_orbs))
+        app.add_event::<OrbCollected>()
+            .add_system_set(SystemSet::on_enter(State::Game).with_system(spawn_starting_orbs))
             .add_system_set(
                 SystemSet::on_update(State::Game)
-                    .with_system(player_pickup.label("player_pickup"))
-                    .with_system(respawn_orbs)
-                    .with_system(change_player_size.after("player_pickup")),
+                    .with_system(
+                        player_pickup
+                            .label("player_pickup")
+                            .after("collision_detection"),
+                    )
+                    .with_system(respawn_orbs.after("collision_detection"))
+                    .with_system(attract_orbs.before("collision_detection"))
+                    .with_system(feeding_frenzy)
+                    .with_system(expire_fleeting_orbs)
+                    .with_system(
+                        change_player_size
+                            .after("player_pickup")
+                            .after("update_inflation"),
+                    ),
             );
     }
 }
@@ -27,6 +48,51 @@ pub struct EnergyOrb(pub Vec2);
 #[derive(Component)]
 pub struct RespawnTimer(Timer);
 
+/// Sent whenever a player gains energy from an orb.
+pub struct OrbCollected {
+    pub player: Entity,
+    pub energy: f32,
+}
+
+/// An orb which disappears for good once eaten or expired.
+#[derive(Component)]
+struct FleetingOrb(Timer);
+
+struct FrenzyTimer(Timer);
+
+#[derive(Clone, Component, Copy, Debug, PartialEq)]
+pub enum OrbSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl OrbSize {
+    const ALL: [OrbSize; 3] = [OrbSize::Small, OrbSize::Medium, OrbSize::Large];
+
+    fn random(weights: [u32; 3]) -> Self {
+        *Self::ALL
+            .choose_weighted(&mut thread_rng(), |s| weights[*s as usize])
+            .unwrap_or(&OrbSize::Medium)
+    }
+
+    fn scale(self) -> f32 {
+        match self {
+            OrbSize::Small => 0.2,
+            OrbSize::Medium => 0.3,
+            OrbSize::Large => 0.45,
+        }
+    }
+
+    fn energy(self) -> f32 {
+        match self {
+            OrbSize::Small => 1.5,
+            OrbSize::Medium => 3.0,
+            OrbSize::Large => 6.0,
+        }
+    }
+}
+
 const ORB_POSITIONS: [(f32, f32); 8] = [
     (LOGICAL_WIDTH as f32 * 0.45, LOGICAL_HEIGHT as f32 * 0.575),
     (LOGICAL_WIDTH as f32 * 0.45, LOGICAL_HEIGHT as f32 * 0.425),
@@ -38,57 +104,162 @@ const ORB_POSITIONS: [(f32, f32); 8] = [
     (LOGICAL_WIDTH as f32 * 0.90, LOGICAL_HEIGHT as f32 * 0.10),
 ];
 
-const ORB_SCALE: f32 = 0.3;
-
-fn spawn_starting_orbs(mut commands: Commands, asset_server: Res<AssetServer>) {
-    for (x, y) in ORB_POSITIONS.iter().copied() {
-        commands
-            .spawn()
-            .insert(EnergyOrb(Vec2::new(x, y)))
-            .insert_bundle(SpriteBundle {
-                texture: asset_server.load("images/orb.png"),
-                transform: Transform::from_scale(Vec3::splat(ORB_SCALE))
-                    .with_translation(Vec3::new(x, y, 0.0)),
-                visibility: Visibility { is_visible: false },
-                ..default()
-            })
-            .insert(CollisionCircle {
-                radius: 64.0 * ORB_SCALE,
-            })
+/// How far from any player a randomly placed orb must appear.
+const ORB_PLAYER_CLEARANCE: f32 = 150.0;
+
+/// The most energy a fish can hold, however it comes by it.
+pub const MAX_ENERGY: f32 = 20.0;
+
+fn spawn_orb<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    asset_server: &Res<AssetServer>,
+    position: Vec2,
+    size: OrbSize,
+    visible: bool,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut orb = commands.spawn();
+
+    orb.insert(EnergyOrb(position))
+        .insert(size)
+        .insert_bundle(SpriteBundle {
+            texture: asset_server.load("images/orb.png"),
+            transform: Transform::from_scale(Vec3::splat(size.scale()))
+                .with_translation(position.extend(0.0)),
+            visibility: Visibility {
+                is_visible: visible,
+            },
+            ..default()
+        })
+        .insert(CollisionCircle {
+            radius: 64.0 * size.scale(),
+        })
+        .insert(CollisionLayers::new(CollisionLayer::Orb).with_filter(CollisionLayer::Player));
+
+    orb
+}
+
+/// Spawns an orb that won't respawn, and that fades away if nobody eats it in time.
+pub fn spawn_fleeting_orb(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec2,
+    size: OrbSize,
+    lifetime: f32,
+) {
+    spawn_orb(commands, asset_server, position, size, true)
+        .insert(FleetingOrb(Timer::from_seconds(lifetime, false)));
+}
+
+fn spawn_starting_orbs(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    bounds: Res<ArenaBounds>,
+    player_configuration: Res<PlayerConfiguration>,
+) {
+    let orb_count = match ruleset.orbs_per_player {
+        Some(orbs_per_player) => orbs_per_player * player_configuration.0.iter().flatten().count(),
+        None => ORB_POSITIONS.len(),
+    };
+
+    let orb_count = if ruleset.orb_placement == OrbPlacement::Fixed {
+        orb_count.min(ORB_POSITIONS.len())
+    } else {
+        orb_count
+    };
+
+    let mut occupied = Vec::new();
+
+    for (x, y) in ORB_POSITIONS.iter().copied().cycle().take(orb_count) {
+        let size = OrbSize::random(ruleset.orb_size_weights);
+        let radius = 64.0 * size.scale();
+
+        // Randomly placed orbs keep clear of each other, falling back on the fixed spots only if
+        // the arena is too crowded to find room.
+        let position = match ruleset.orb_placement {
+            OrbPlacement::Fixed => Vec2::new(x, y),
+            OrbPlacement::Random => {
+                random_clear_position(&bounds, radius, &occupied).unwrap_or_else(|| Vec2::new(x, y))
+            }
+        };
+
+        occupied.push((position, radius));
+
+        spawn_orb(&mut commands, &asset_server, position, size, false)
             .insert(RespawnTimer(Timer::from_seconds(1.0, false)));
     }
+
+    if let Some(frenzy) = &ruleset.feeding_frenzy {
+        commands.insert_resource(FrenzyTimer(Timer::from_seconds(frenzy.interval_secs, true)));
+    }
 }
 
 fn player_pickup(
     mut commands: Commands,
-    mut players: Query<(Entity, &mut Energy, &Transform, &CollisionCircle), With<Player>>,
-    mut orbs: Query<(Entity, &mut Visibility, &EnergyOrb, &CollisionCircle), Without<RespawnTimer>>,
+    contacts: Res<Contacts>,
+    ruleset: Res<Ruleset>,
+    mut collected: EventWriter<OrbCollected>,
+    mut players: Query<(Entity, &mut Energy, &Team, Option<&Dead>), With<Player>>,
+    mut orbs: Query<
+        (
+            Entity,
+            &mut Visibility,
+            &mut Transform,
+            &EnergyOrb,
+            &OrbSize,
+            Option<&FleetingOrb>,
+        ),
+        Without<RespawnTimer>,
+    >,
 ) {
-    const MAX_ENERGY: f32 = 20.0;
-    const ORB_ENERGY_BOOST: f32 = 3.0;
-    const ORB_RESPAWN_SECS: f32 = 15.0;
-
-    for (orb_entity, mut orb_visibility, orb, orb_collision) in orbs.iter_mut() {
-        let collision = players
-            .iter_mut()
-            .map(|(p, e, t, c)| {
-                (
-                    p,
-                    e,
-                    (orb.0 - t.translation.truncate()).length() - c.radius - orb_collision.radius,
-                )
-            })
-            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
-            .filter(|a| a.2 <= 0.0)
-            .filter(|a| a.1 .0 < MAX_ENERGY);
-
-        if let Some((player_entity, mut player_energy, _)) = collision {
-            orb_visibility.is_visible = false;
-            commands
-                .entity(orb_entity)
-                .insert(RespawnTimer(Timer::from_seconds(ORB_RESPAWN_SECS, false)));
-            player_energy.0 = (player_energy.0 + ORB_ENERGY_BOOST).min(MAX_ENERGY);
-            println!("Player {:?} energy: {}", player_entity, player_energy.0);
+    for (orb_entity, mut orb_visibility, mut orb_transform, orb, orb_size, fleeting_orb) in
+        orbs.iter_mut()
+    {
+        let collision = contacts
+            .colliding_with(orb_entity)
+            .find(|p| matches!(players.get(*p), Ok((_, energy, _, _)) if energy.0 < MAX_ENERGY));
+
+        if let Some(player_entity) = collision {
+            if fleeting_orb.is_some() {
+                commands.entity(orb_entity).despawn();
+            } else {
+                orb_visibility.is_visible = false;
+                orb_transform.translation = orb.0.extend(0.0);
+                commands
+                    .entity(orb_entity)
+                    .insert(RespawnTimer(Timer::from_seconds(
+                        ruleset.orb_respawn_secs,
+                        false,
+                    )));
+            }
+
+            let (_, _, &team, _) = players.get(player_entity).expect("cannot find player");
+
+            let recipients = if ruleset.team_orb_sharing {
+                players
+                    .iter()
+                    .filter(|(_, _, t, dead)| **t == team && dead.is_none())
+                    .map(|(p, _, _, _)| p)
+                    .collect::<Vec<_>>()
+            } else {
+                vec![player_entity]
+            };
+
+            let share = orb_size.energy() / recipients.len() as f32;
+
+            for recipient in recipients {
+                let (_, mut energy, _, _) = players.get_mut(recipient).expect("cannot find player");
+
+                let gained = share.min(MAX_ENERGY - energy.0).max(0.0);
+
+                energy.0 += gained;
+                debug!("Player {:?} energy: {}", recipient, energy.0);
+
+                collected.send(OrbCollected {
+                    player: recipient,
+                    energy: gained,
+                });
+            }
         }
     }
 }
@@ -96,59 +267,215 @@ fn player_pickup(
 fn respawn_orbs(
     mut commands: Commands,
     time: Res<Time>,
-    mut orbs: Query<(
-        Entity,
-        &mut Visibility,
-        &mut RespawnTimer,
-        &EnergyOrb,
-        &CollisionCircle,
-    )>,
-    players: Query<(&Transform, &CollisionCircle), With<Player>>,
+    contacts: Res<Contacts>,
+    ruleset: Res<Ruleset>,
+    bounds: Res<ArenaBounds>,
+    mut orbs: Query<
+        (
+            Entity,
+            &mut Visibility,
+            &mut RespawnTimer,
+            &mut EnergyOrb,
+            &mut OrbSize,
+            &mut Transform,
+            &mut CollisionCircle,
+        ),
+        Without<Player>,
+    >,
+    players: Query<(Entity, &Transform, &CollisionCircle), With<Player>>,
+    placed_orbs: Query<(&Transform, &CollisionCircle), (With<EnergyOrb>, Without<RespawnTimer>)>,
 ) {
-    for (orb_entity, mut orb_visibility, mut respawn_timer, orb, orb_collision) in orbs.iter_mut() {
+    // Randomly placed orbs keep away from players, and from each other.
+    let mut occupied = players
+        .iter()
+        .map(|(_, t, c)| (t.translation.truncate(), c.radius + ORB_PLAYER_CLEARANCE))
+        .chain(
+            placed_orbs
+                .iter()
+                .map(|(t, c)| (t.translation.truncate(), c.radius)),
+        )
+        .collect::<Vec<_>>();
+
+    for (
+        orb_entity,
+        mut orb_visibility,
+        mut respawn_timer,
+        mut orb,
+        mut orb_size,
+        mut orb_transform,
+        mut orb_collision,
+    ) in orbs.iter_mut()
+    {
         respawn_timer
             .0
             .tick(Duration::from_secs_f32(time.delta_seconds()));
 
-        if respawn_timer.0.finished()
-            && !players
-                .iter()
-                .map(|(t, c)| {
-                    (orb.0 - t.translation.truncate()).length() - c.radius - orb_collision.radius
-                })
-                .any(|d| d <= 0.0)
+        if !respawn_timer.0.finished() {
+            continue;
+        }
+
+        if ruleset.orb_placement == OrbPlacement::Random {
+            let size = OrbSize::random(ruleset.orb_size_weights);
+
+            match random_clear_position(&bounds, 64.0 * size.scale(), &occupied) {
+                Some(position) => {
+                    occupied.push((position, 64.0 * size.scale()));
+                    orb.0 = position;
+                    *orb_size = size;
+                    orb_transform.translation = position.extend(0.0);
+                    orb_transform.scale = Vec3::splat(size.scale());
+                    orb_collision.radius = 64.0 * size.scale();
+                }
+                None => continue,
+            }
+        } else if contacts
+            .colliding_with(orb_entity)
+            .any(|e| players.get(e).is_ok())
         {
-            orb_visibility.is_visible = true;
-            commands.entity(orb_entity).remove::<RespawnTimer>();
+            continue;
+        } else {
+            // Fixed spots left outside a shrinking arena are pulled in to its edge.
+            orb_transform.translation = orb
+                .0
+                .clamp(
+                    bounds.min + orb_collision.radius,
+                    bounds.max - orb_collision.radius,
+                )
+                .extend(0.0);
+        }
+
+        orb_visibility.is_visible = true;
+        commands.entity(orb_entity).remove::<RespawnTimer>();
+    }
+}
+
+fn feeding_frenzy(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    bounds: Res<ArenaBounds>,
+    frenzy_timer: Option<ResMut<FrenzyTimer>>,
+    players: Query<(&Transform, &CollisionCircle), With<Player>>,
+) {
+    let (mut frenzy_timer, frenzy) = match (frenzy_timer, &ruleset.feeding_frenzy) {
+        (Some(frenzy_timer), Some(frenzy)) => (frenzy_timer, frenzy),
+        _ => return,
+    };
+
+    if !frenzy_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let occupied = players
+        .iter()
+        .map(|(t, c)| (t.translation.truncate(), c.radius + ORB_PLAYER_CLEARANCE))
+        .collect::<Vec<_>>();
+
+    if let Some(center) = random_clear_position(&bounds, frenzy.spread, &occupied) {
+        let mut rng = thread_rng();
+
+        for _ in 0..frenzy.orb_count {
+            let offset = Vec2::new(
+                rng.gen_range(-frenzy.spread..frenzy.spread),
+                rng.gen_range(-frenzy.spread..frenzy.spread),
+            )
+            .clamp_length_max(frenzy.spread);
+
+            // A wide spread near the edge mustn't drop orbs outside the arena.
+            let radius = 64.0 * OrbSize::Small.scale();
+            let position = (center + offset).clamp(bounds.min + radius, bounds.max - radius);
+
+            spawn_fleeting_orb(
+                &mut commands,
+                &asset_server,
+                position,
+                OrbSize::Small,
+                frenzy.orb_lifetime,
+            );
+        }
+
+        spawn_bubble_group(
+            &mut commands,
+            &asset_server,
+            center.extend(0.0),
+            10,
+            -frenzy.spread..frenzy.spread,
+            -frenzy.spread..frenzy.spread,
+            0.0..0.001,
+        );
+    }
+}
+
+fn expire_fleeting_orbs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut orbs: Query<(Entity, &mut FleetingOrb, &mut Sprite)>,
+) {
+    const FADE_SECS: f32 = 1.0;
+
+    for (orb_entity, mut fleeting_orb, mut sprite) in orbs.iter_mut() {
+        fleeting_orb.0.tick(time.delta());
+
+        let remaining = fleeting_orb.0.duration().as_secs_f32() - fleeting_orb.0.elapsed_secs();
+        sprite.color.set_a((remaining / FADE_SECS).min(1.0));
+
+        if fleeting_orb.0.finished() {
+            commands.entity(orb_entity).despawn();
+        }
+    }
+}
+
+fn attract_orbs(
+    mut orbs: Query<&mut Transform, (With<EnergyOrb>, Without<RespawnTimer>, Without<Player>)>,
+    players: Query<&Transform, (With<Player>, With<Magnet>)>,
+) {
+    const MAGNET_RADIUS: f32 = 400.0;
+    const MAGNET_SPEED: f32 = 6.0;
+
+    for mut orb_transform in orbs.iter_mut() {
+        let pull = players
+            .iter()
+            .map(|t| (t.translation - orb_transform.translation).truncate())
+            .filter(|v| v.length() < MAGNET_RADIUS)
+            .min_by(|a, b| a.length().partial_cmp(&b.length()).unwrap());
+
+        if let Some(pull) = pull {
+            orb_transform.translation += (pull.normalize_or_zero() * MAGNET_SPEED).extend(0.0);
         }
     }
 }
 
 fn change_player_size(
+    ruleset: Res<Ruleset>,
     mut players: Query<
         (
             &mut Transform,
             &mut CollisionCircle,
             &Energy,
+            &Species,
             Option<&Shielded>,
+            Option<&Inflated>,
         ),
         With<Player>,
     >,
 ) {
-    const ENERGY_SCALE_MULTIPLIER: f32 = 1.06;
-
-    for (mut transform, mut collision_circle, energy, shielded) in players.iter_mut() {
-        let target_scale_factor = PLAYER_SCALE * ENERGY_SCALE_MULTIPLIER.powf(energy.0);
+    for (mut transform, mut collision_circle, energy, species, shielded, inflated) in
+        players.iter_mut()
+    {
+        let target_scale_factor = PLAYER_SCALE * ruleset.size_stats.size(energy.0);
         let next_scale_factor =
             transform.scale.z + (target_scale_factor - transform.scale.z) * 0.05;
 
         let base_scale = transform.scale / transform.scale.z;
         transform.scale = base_scale * next_scale_factor;
 
+        // A shield covers all but the biggest fish, such as a puffed up pufferfish.
+        let body_scale = species.collision_scale(inflated.is_some());
         let shield_target_size = if shielded.is_some() {
-            target_scale_factor * PLAYER_SHIELD_SCALE * 128.0
+            target_scale_factor * body_scale.max(PLAYER_SHIELD_SCALE) * 128.0
         } else {
-            target_scale_factor * 128.0
+            target_scale_factor * body_scale * 128.0
         };
 
         collision_circle.radius += (shield_target_size - collision_circle.radius) * 0.10;
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+
+use crate::core_components::{Projectile, Velocity};
+use crate::State;
+
+pub struct InkPlugin;
+
+impl Plugin for InkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(State::Game)
+                .with_system(fade_ink)
+                .with_system(
+                    slow_projectiles_in_ink
+                        .label("physics")
+                        .before("move_projectiles"),
+                ),
+        );
+    }
+}
+
+const INK_SECS: f32 = 4.0;
+/// Seconds at the end of a cloud's life over which it fades away.
+const INK_FADE_SECS: f32 = 1.0;
+const INK_RADIUS: f32 = 200.0;
+const INK_ALPHA: f32 = 0.9;
+/// How much of its speed a projectile loses each frame it spends in ink.
+const INK_DRAG: f32 = 0.08;
+
+/// A cloud of ink.  Fish inside it can't be seen, and projectiles struggle through it.
+#[derive(Component)]
+pub struct InkCloud {
+    radius: f32,
+    timer: Timer,
+}
+
+pub fn spawn_ink_cloud(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Vec2) {
+    // Drawn by the foreground camera along with the fish, so the cloud covers them, and the orb
+    // texture's soft edges keep it from looking like a hole in the arena.
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("images/orb.png"),
+            // The orb texture is 128 pixels across.
+            transform: Transform::from_scale(Vec3::splat(INK_RADIUS / 64.0))
+                .with_translation(position.extend(4.0)),
+            sprite: Sprite {
+                color: Color::rgba(0.05, 0.05, 0.1, INK_ALPHA),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(RenderLayers::layer(0))
+        .insert(InkCloud {
+            radius: INK_RADIUS,
+            timer: Timer::from_seconds(INK_SECS, false),
+        });
+}
+
+/// Whether something at `position` is hidden inside any of the given ink clouds.
+pub fn hidden_in_ink<'a>(
+    position: Vec2,
+    clouds: impl IntoIterator<Item = (&'a Transform, &'a InkCloud)>,
+) -> bool {
+    clouds.into_iter().any(|(transform, cloud)| {
+        (transform.translation.truncate() - position).length() < cloud.radius
+    })
+}
+
+fn fade_ink(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut clouds: Query<(Entity, &mut InkCloud, &mut Sprite)>,
+) {
+    for (entity, mut cloud, mut sprite) in clouds.iter_mut() {
+        if cloud.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let remaining = cloud.timer.duration().as_secs_f32() - cloud.timer.elapsed_secs();
+        sprite
+            .color
+            .set_a(INK_ALPHA * (remaining / INK_FADE_SECS).min(1.0));
+    }
+}
+
+fn slow_projectiles_in_ink(
+    clouds: Query<(&Transform, &InkCloud)>,
+    mut projectiles: Query<(&Transform, &mut Velocity), With<Projectile>>,
+) {
+    for (transform, mut velocity) in projectiles.iter_mut() {
+        if hidden_in_ink(transform.translation.truncate(), clouds.iter()) {
+            velocity.0 *= 1.0 - INK_DRAG;
+        }
+    }
+}
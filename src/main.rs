@@ -3,38 +3,57 @@ use bevy::render::view::RenderLayers;
 
 use self::background::BackgroundPlugin;
 use self::big_fish::BigFishPlugin;
+use self::collision::CollisionPlugin;
 use self::configuration::ConfigurationPlugin;
 use self::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
-use self::core_components::{HitPoints, Lives};
+use self::core_components::{HitPoints, Lives, Persistent};
+use self::currents::CurrentsPlugin;
 use self::energy_orbs::EnergyOrbsPlugin;
+use self::ink::InkPlugin;
+use self::match_flow::MatchFlowPlugin;
+use self::pickups::PickupsPlugin;
 use self::player::{
-    KeyMap, PlayerColor, PlayerConfiguration, PlayerConfigurationBundle, PlayerPlugin,
+    KeyMap, PlayerColor, PlayerConfiguration, PlayerConfigurationBundle, PlayerPlugin, Species,
+    Team, Weapon,
 };
 use self::render::additional_pass::AdditionalPassPlugin;
 use self::render::cameras::{setup_cameras, ForegroundCamera, FOREGROUND_COLOR_TEXTURE};
+use self::sudden_death::SuddenDeathPlugin;
 
 mod animation;
 mod background;
 mod big_fish;
+mod collision;
 mod configuration;
 mod core_components;
+mod currents;
 mod energy_orbs;
+mod ink;
+mod match_flow;
+mod pickups;
 mod player;
 mod render;
+mod sudden_death;
 
 fn main() {
     App::new()
         .add_plugin(ConfigurationPlugin)
         .add_plugins(DefaultPlugins)
         .add_plugin(BackgroundPlugin)
+        .add_plugin(CollisionPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(EnergyOrbsPlugin)
+        .add_plugin(PickupsPlugin)
+        .add_plugin(MatchFlowPlugin)
+        .add_plugin(SuddenDeathPlugin)
+        .add_plugin(CurrentsPlugin)
+        .add_plugin(InkPlugin)
         .add_plugin(AdditionalPassPlugin::<ForegroundCamera>::new(
             "foreground_pass",
             None,
         ))
         .add_plugin(BigFishPlugin)
-        .add_state(State::Game)
+        .add_state(State::Menu)
         .add_startup_system(setup_cameras)
         .add_startup_system(setup)
         .run();
@@ -61,7 +80,8 @@ fn setup(
             )),
             ..default()
         })
-        .insert(RenderLayers::layer(1));
+        .insert(RenderLayers::layer(1))
+        .insert(Persistent);
 
     const DEFAULT_PLAYER_KEY_MAPS: [KeyMap; 4] = [
         KeyMap {
@@ -69,24 +89,32 @@ fn setup(
             left: KeyCode::A,
             right: KeyCode::D,
             shoot: KeyCode::S,
+            dash: None,
+            ability: KeyCode::E,
         },
         KeyMap {
             forward: KeyCode::I,
             left: KeyCode::J,
             right: KeyCode::L,
             shoot: KeyCode::K,
+            dash: None,
+            ability: KeyCode::O,
         },
         KeyMap {
             forward: KeyCode::Up,
             left: KeyCode::Left,
             right: KeyCode::Right,
             shoot: KeyCode::Down,
+            dash: None,
+            ability: KeyCode::RShift,
         },
         KeyMap {
             forward: KeyCode::Numpad8,
             left: KeyCode::Numpad4,
             right: KeyCode::Numpad6,
             shoot: KeyCode::Numpad5,
+            dash: None,
+            ability: KeyCode::Numpad9,
         },
     ];
 
@@ -103,6 +131,9 @@ fn setup(
             color: PlayerColor(DEFAULT_PLAYER_COLORS[i]),
             hp: HitPoints(5),
             lives: Lives(3),
+            weapon: Weapon::Standard,
+            team: Team(i),
+            species: Species::Classic,
         })
     }
 }
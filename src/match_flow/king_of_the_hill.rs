@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::collision::{random_clear_position, ArenaBounds};
+use crate::configuration::{GameMode, Ruleset, LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::core_components::Dead;
+use crate::player::{Player, Team};
+
+use super::{MatchOver, Scores, Winner};
+
+/// The zone teams score by holding.  It keeps swimming towards `waypoint`, then picks another.
+#[derive(Component)]
+pub(super) struct Hill {
+    waypoint: Vec2,
+}
+
+pub(super) fn spawn_hill(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+) {
+    let zone_radius = match ruleset.game_mode {
+        GameMode::KingOfTheHill { zone_radius, .. } => zone_radius,
+        _ => return,
+    };
+
+    let center = Vec2::new(LOGICAL_WIDTH as f32 / 2.0, LOGICAL_HEIGHT as f32 / 2.0);
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("images/orb.png"),
+            // The orb texture is 128 pixels across.
+            transform: Transform::from_scale(Vec3::splat(zone_radius / 64.0))
+                .with_translation(center.extend(0.2)),
+            sprite: Sprite {
+                color: Color::rgba(1.0, 1.0, 1.0, 0.15),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Hill { waypoint: center });
+}
+
+pub(super) fn move_hill(
+    ruleset: Res<Ruleset>,
+    bounds: Res<ArenaBounds>,
+    mut hill: Query<(&mut Transform, &mut Hill)>,
+) {
+    let (zone_radius, zone_speed) = match ruleset.game_mode {
+        GameMode::KingOfTheHill {
+            zone_radius,
+            zone_speed,
+            ..
+        } => (zone_radius, zone_speed),
+        _ => return,
+    };
+
+    for (mut transform, mut hill) in hill.iter_mut() {
+        let position = transform.translation.truncate();
+        let offset = hill.waypoint - position;
+
+        if offset.length() <= zone_speed {
+            transform.translation = hill.waypoint.extend(transform.translation.z);
+
+            if let Some(waypoint) = random_clear_position(&bounds, zone_radius, &[]) {
+                hill.waypoint = waypoint;
+            }
+        } else {
+            transform.translation += (offset.normalize() * zone_speed).extend(0.0);
+        }
+    }
+}
+
+pub(super) fn score_hill(
+    time: Res<Time>,
+    ruleset: Res<Ruleset>,
+    mut scores: ResMut<Scores>,
+    mut match_over: EventWriter<MatchOver>,
+    mut hill: Query<(&Transform, &mut Sprite), With<Hill>>,
+    players: Query<(&Transform, &Team), (With<Player>, Without<Dead>)>,
+) {
+    let (target_score, zone_radius) = match ruleset.game_mode {
+        GameMode::KingOfTheHill {
+            target_score,
+            zone_radius,
+            ..
+        } => (target_score, zone_radius),
+        _ => return,
+    };
+
+    for (hill_transform, mut sprite) in hill.iter_mut() {
+        let center = hill_transform.translation.truncate();
+
+        let holders = players
+            .iter()
+            .filter(|(t, _)| (t.translation.truncate() - center).length() < zone_radius)
+            .map(|(_, team)| *team)
+            .collect::<HashSet<_>>();
+
+        // A zone held by a single team takes on its colour and scores for it.  An empty or
+        // contested one stays white and scores for nobody.
+        let holder = match holders.len() {
+            1 => holders.into_iter().next(),
+            _ => None,
+        };
+
+        sprite.color = match holder {
+            Some(team) => team.color(),
+            None => Color::WHITE,
+        };
+        sprite.color.set_a(0.15);
+
+        if let Some(team) = holder {
+            let before = scores.0.get(&team).copied().unwrap_or(0.0);
+            let score = scores.add(team, time.delta_seconds());
+
+            if score.floor() > before.floor() {
+                println!("Team {} score: {}", team.0 + 1, score.floor());
+            }
+
+            if score >= target_score {
+                match_over.send(MatchOver(Winner::Team(team)));
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::configuration::{GameMode, Ruleset};
+use crate::core_components::Dead;
+use crate::player::{Player, PlayerConfiguration, Team};
+
+use super::{MatchOver, Winner};
+
+pub(super) fn check_last_team_standing(
+    ruleset: Res<Ruleset>,
+    player_configuration: Res<PlayerConfiguration>,
+    mut match_over: EventWriter<MatchOver>,
+    players: Query<(&Team, Option<&Dead>), With<Player>>,
+) {
+    if ruleset.game_mode != GameMode::LastFishStanding
+        || ruleset.boss_fight.is_some()
+        || players.is_empty()
+    {
+        return;
+    }
+
+    let team_count = player_configuration
+        .0
+        .iter()
+        .flatten()
+        .map(|p| p.team)
+        .collect::<HashSet<_>>()
+        .len();
+
+    // Someone practicing alone, or with only teammates, can't win by outlasting anyone.
+    if team_count < 2 {
+        return;
+    }
+
+    let living_teams = players
+        .iter()
+        .filter(|(_, dead)| dead.is_none())
+        .map(|(team, _)| *team)
+        .collect::<Vec<_>>();
+
+    if living_teams.is_empty() {
+        match_over.send(MatchOver(Winner::Nobody));
+    } else if living_teams.iter().all(|t| *t == living_teams[0]) {
+        match_over.send(MatchOver(Winner::Team(living_teams[0])));
+    }
+}
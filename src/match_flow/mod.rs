@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::configuration::{GameMode, Ruleset};
+use crate::core_components::Persistent;
+use crate::player::Team;
+use crate::State;
+
+use self::king_of_the_hill::{move_hill, score_hill, spawn_hill};
+use self::last_fish_standing::check_last_team_standing;
+use self::orb_hoarder::score_orbs;
+use self::timed_deathmatch::{respawn_players, score_kills, Respawns};
+
+mod king_of_the_hill;
+mod last_fish_standing;
+mod orb_hoarder;
+mod timed_deathmatch;
+
+pub struct MatchFlowPlugin;
+
+impl Plugin for MatchFlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MatchOver>()
+            .insert_resource(Scores::default())
+            .insert_resource(MatchClock::default())
+            .insert_resource(Respawns::default())
+            .insert_resource(Rematch::default())
+            .add_system_set(SystemSet::on_exit(State::Game).with_system(clear_arena))
+            .add_system_set(
+                SystemSet::on_enter(State::Game)
+                    .with_system(start_match)
+                    .with_system(spawn_hill),
+            )
+            .add_system_set(
+                SystemSet::on_update(State::Game)
+                    .with_system(check_last_team_standing.label("match_conditions"))
+                    .with_system(move_hill.before("score_hill"))
+                    .with_system(score_hill.label("score_hill").label("match_conditions"))
+                    .with_system(score_orbs.label("match_conditions"))
+                    .with_system(score_kills.label("match_conditions"))
+                    .with_system(respawn_players)
+                    .with_system(check_clock.label("match_conditions"))
+                    .with_system(end_match.after("match_conditions")),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Winner {
+    Team(Team),
+    /// Everyone, together, against the big fish.
+    Players,
+    BigFish,
+    Nobody,
+}
+
+/// Sent by whatever decides the match is over.  Only the first one sent ends it.
+pub struct MatchOver(pub Winner);
+
+/// Each team's score in modes that keep one.
+#[derive(Default)]
+struct Scores(HashMap<Team, f32>);
+
+impl Scores {
+    /// Adds to a team's score and returns the new total.
+    fn add(&mut self, team: Team, amount: f32) -> f32 {
+        let score = self.0.entry(team).or_default();
+        *score += amount;
+        *score
+    }
+
+    /// The team with the highest score, unless it's shared.
+    fn leader(&self) -> Winner {
+        let best = self.0.values().copied().fold(f32::MIN, f32::max);
+        let mut leaders = self.0.iter().filter(|(_, s)| **s == best);
+
+        match (leaders.next(), leaders.next()) {
+            (Some((team, _)), None) => Winner::Team(*team),
+            _ => Winner::Nobody,
+        }
+    }
+}
+
+/// Counts down to the end of a timed match.
+#[derive(Default)]
+struct MatchClock(Option<Timer>);
+
+/// Seconds the winner gets to enjoy it before everyone goes back to the lobby.
+const REMATCH_SECS: f32 = 5.0;
+
+/// Counts down to the next match once this one is over.
+#[derive(Default)]
+struct Rematch(Option<Timer>);
+
+fn start_match(
+    ruleset: Res<Ruleset>,
+    mut scores: ResMut<Scores>,
+    mut clock: ResMut<MatchClock>,
+    mut respawns: ResMut<Respawns>,
+) {
+    scores.0.clear();
+    respawns.0.clear();
+
+    clock.0 = match ruleset.game_mode {
+        GameMode::OrbHoarder { time_limit_secs }
+        | GameMode::TimedDeathmatch {
+            time_limit_secs, ..
+        } => Some(Timer::from_seconds(time_limit_secs, false)),
+        GameMode::LastFishStanding | GameMode::KingOfTheHill { .. } => None,
+    };
+}
+
+fn check_clock(
+    time: Res<Time>,
+    scores: Res<Scores>,
+    mut clock: ResMut<MatchClock>,
+    mut match_over: EventWriter<MatchOver>,
+) {
+    if let Some(timer) = &mut clock.0 {
+        if timer.tick(time.delta()).just_finished() {
+            match_over.send(MatchOver(scores.leader()));
+        }
+    }
+}
+
+fn end_match(
+    time: Res<Time>,
+    mut state: ResMut<bevy::ecs::schedule::State<State>>,
+    mut rematch: ResMut<Rematch>,
+    mut match_over: EventReader<MatchOver>,
+) {
+    // Play carries on while the result sinks in, but nothing more can change it.
+    if let Some(timer) = &mut rematch.0 {
+        for _ in match_over.iter() {}
+
+        if timer.tick(time.delta()).finished() && state.set(State::Menu).is_ok() {
+            rematch.0 = None;
+        }
+
+        return;
+    }
+
+    let winner = match match_over.iter().next() {
+        Some(MatchOver(winner)) => *winner,
+        None => return,
+    };
+
+    rematch.0 = Some(Timer::from_seconds(REMATCH_SECS, false));
+
+    match winner {
+        Winner::Team(team) => println!("Team {} wins!", team.0 + 1),
+        Winner::Players => println!("The big fish has been defeated!"),
+        Winner::BigFish => println!("The big fish wins!"),
+        Winner::Nobody => println!("Nobody wins!"),
+    }
+}
+
+/// Clears away everything from the match just played, so the next one starts afresh.
+fn clear_arena(
+    mut commands: Commands,
+    entities: Query<Entity, (Without<Parent>, Without<Camera>, Without<Persistent>)>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
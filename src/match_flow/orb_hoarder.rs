@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+use crate::configuration::{GameMode, Ruleset};
+use crate::energy_orbs::OrbCollected;
+use crate::player::Team;
+
+use super::Scores;
+
+pub(super) fn score_orbs(
+    ruleset: Res<Ruleset>,
+    mut scores: ResMut<Scores>,
+    mut collected: EventReader<OrbCollected>,
+    players: Query<&Team>,
+) {
+    if !matches!(ruleset.game_mode, GameMode::OrbHoarder { .. }) {
+        return;
+    }
+
+    for OrbCollected { player, energy } in collected.iter() {
+        if let Ok(team) = players.get(*player) {
+            let score = scores.add(*team, *energy);
+            println!("Team {} score: {}", team.0 + 1, score);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::collision::{random_clear_position, ArenaBounds};
+use crate::configuration::{GameMode, Ruleset};
+use crate::core_components::{CollisionCircle, Dead, KilledBy};
+use crate::player::{
+    spawn_player, KeyMap, Player, PlayerConfiguration, PlayerConfigurationBundle, Team,
+    PLAYER_SCALE,
+};
+
+use super::Scores;
+
+/// How far from any other fish a respawning one appears.
+const RESPAWN_CLEARANCE: f32 = 200.0;
+
+/// Fish waiting to come back, and what they'll come back as.
+#[derive(Default)]
+pub(super) struct Respawns(pub(super) Vec<(Timer, PlayerConfigurationBundle)>);
+
+pub(super) fn score_kills(
+    ruleset: Res<Ruleset>,
+    player_configuration: Res<PlayerConfiguration>,
+    mut scores: ResMut<Scores>,
+    mut respawns: ResMut<Respawns>,
+    killed: Query<(&Team, &KeyMap, Option<&KilledBy>), (With<Player>, Added<Dead>)>,
+    teams: Query<&Team>,
+) {
+    let respawn_secs = match ruleset.game_mode {
+        GameMode::TimedDeathmatch { respawn_secs, .. } => respawn_secs,
+        _ => return,
+    };
+
+    for (team, keymap, killed_by) in killed.iter() {
+        let killer_team = killed_by.and_then(|k| teams.get(k.0).ok());
+
+        // Being eaten by the big fish or shot by a teammate doesn't count for anyone.
+        if let Some(killer_team) = killer_team.filter(|t| *t != team) {
+            let score = scores.add(*killer_team, 1.0);
+            println!("Team {} score: {}", killer_team.0 + 1, score);
+        }
+
+        let configuration = player_configuration
+            .0
+            .iter()
+            .flatten()
+            .find(|c| c.keymap == *keymap);
+
+        if let Some(configuration) = configuration {
+            respawns.0.push((
+                Timer::from_seconds(respawn_secs, false),
+                configuration.clone(),
+            ));
+        }
+    }
+}
+
+pub(super) fn respawn_players(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    bounds: Res<ArenaBounds>,
+    player_configuration: Res<PlayerConfiguration>,
+    mut respawns: ResMut<Respawns>,
+    players: Query<(&Transform, &CollisionCircle), (With<Player>, Without<Dead>)>,
+) {
+    for (timer, _) in respawns.0.iter_mut() {
+        timer.tick(time.delta());
+    }
+
+    let (ready, waiting): (Vec<_>, Vec<_>) = respawns.0.drain(..).partition(|(t, _)| t.finished());
+    respawns.0 = waiting;
+
+    let mut occupied = players
+        .iter()
+        .map(|(t, c)| (t.translation.truncate(), c.radius + RESPAWN_CLEARANCE))
+        .collect::<Vec<_>>();
+
+    for (_, configuration) in ready {
+        let position = random_clear_position(&bounds, 0.0, &occupied)
+            .unwrap_or((bounds.min + bounds.max) / 2.0);
+
+        // Fish coming back at the same time mustn't land on each other either.
+        let radius = 128.0 * PLAYER_SCALE * configuration.species.collision_scale(false);
+        occupied.push((position, radius + RESPAWN_CLEARANCE));
+
+        spawn_player(
+            &mut commands,
+            &asset_server,
+            &player_configuration,
+            &configuration,
+            position,
+            Quat::from_rotation_z(thread_rng().gen_range(-PI..PI)),
+        );
+
+        println!("Team {} fish respawned", configuration.team.0 + 1);
+    }
+}
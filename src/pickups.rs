@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+
+use crate::background::spawn_bubble_group;
+use crate::collision::{
+    random_clear_position, ArenaBounds, CollisionLayer, CollisionLayers, CollisionStarted,
+};
+use crate::core_components::{CollisionCircle, Dead};
+use crate::player::{Player, PowerUp, Weapon};
+use crate::State;
+
+pub struct PickupsPlugin;
+
+impl Plugin for PickupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PickupTimer(Timer::from_seconds(PICKUP_INTERVAL_SECS, true)))
+            .add_system_set(
+                SystemSet::on_update(State::Game)
+                    .with_system(spawn_pickups)
+                    .with_system(collect_pickups.after("collision_detection")),
+            );
+    }
+}
+
+const PICKUP_INTERVAL_SECS: f32 = 12.0;
+const PICKUP_SCALE: f32 = 0.3;
+const MAX_PICKUPS: usize = 2;
+
+#[derive(Clone, Component, Copy, Debug)]
+pub enum Pickup {
+    Weapon(Weapon),
+    PowerUp(PowerUp),
+}
+
+impl Pickup {
+    fn random() -> Self {
+        let mut rng = thread_rng();
+
+        if rng.gen_bool(0.5) {
+            Pickup::Weapon(*Weapon::ALL.choose(&mut rng).unwrap())
+        } else {
+            Pickup::PowerUp(*PowerUp::ALL.choose(&mut rng).unwrap())
+        }
+    }
+
+    fn texture(self) -> &'static str {
+        match self {
+            Pickup::Weapon(weapon) => weapon.texture(),
+            Pickup::PowerUp(_) => "images/orb.png",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Pickup::Weapon(weapon) => weapon.color(),
+            Pickup::PowerUp(power_up) => power_up.color(),
+        }
+    }
+}
+
+struct PickupTimer(Timer);
+
+fn spawn_pickups(
+    mut commands: Commands,
+    mut timer: ResMut<PickupTimer>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    bounds: Res<ArenaBounds>,
+    pickups: Query<&Pickup>,
+    players: Query<(&Transform, &CollisionCircle), With<Player>>,
+) {
+    timer.0.tick(time.delta());
+
+    if !timer.0.just_finished() || pickups.iter().count() >= MAX_PICKUPS {
+        return;
+    }
+
+    let circle = CollisionCircle {
+        radius: 128.0 * PICKUP_SCALE,
+    };
+
+    let occupied = players
+        .iter()
+        .map(|(t, c)| (t.translation.truncate(), c.radius))
+        .collect::<Vec<_>>();
+
+    let position = random_clear_position(&bounds, circle.radius, &occupied);
+
+    if let Some(position) = position {
+        let pickup = Pickup::random();
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load("images/bubble.png"),
+                transform: Transform::from_scale(Vec3::splat(PICKUP_SCALE))
+                    .with_translation(position.extend(0.5)),
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(pickup)
+            .insert(circle)
+            .insert(
+                CollisionLayers::new(CollisionLayer::Pickup).with_filter(CollisionLayer::Player),
+            )
+            .with_children(|bubble| {
+                bubble.spawn_bundle(SpriteBundle {
+                    texture: asset_server.load(pickup.texture()),
+                    transform: Transform::from_translation(Vec3::new(0.0, 0.0, -0.1)),
+                    sprite: Sprite {
+                        color: pickup.color(),
+                        ..default()
+                    },
+                    ..default()
+                });
+            });
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut collisions: EventReader<CollisionStarted>,
+    pickups: Query<(&Pickup, &Transform)>,
+    players: Query<Entity, (With<Player>, Without<Dead>)>,
+) {
+    let mut collected = HashSet::new();
+
+    for collision in collisions.iter() {
+        let (pickup_entity, player) = match collision.ordered(|e| pickups.get(e).is_ok()) {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        if players.get(player).is_err() || !collected.insert(pickup_entity) {
+            continue;
+        }
+
+        let (pickup, transform) = pickups.get(pickup_entity).expect("cannot find pickup");
+
+        match *pickup {
+            Pickup::Weapon(weapon) => {
+                commands.entity(player).insert(weapon);
+                println!("Player {:?} weapon: {:?}", player, weapon);
+            }
+            Pickup::PowerUp(power_up) => {
+                power_up.apply(&mut commands.entity(player));
+                println!("Player {:?} power-up: {:?}", player, power_up);
+            }
+        }
+
+        commands.entity(pickup_entity).despawn_recursive();
+
+        spawn_bubble_group(
+            &mut commands,
+            &asset_server,
+            transform.translation,
+            5,
+            -30.0..30.0,
+            -30.0..30.0,
+            0.0..0.001,
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::prelude::*;
+
+use crate::background::spawn_bubble_group;
+use crate::collision::CollisionStarted;
+use crate::configuration::Ruleset;
+use crate::core_components::{Dead, Energy, HitPoints, KilledBy, Shielded, Velocity};
+use crate::ink::spawn_ink_cloud;
+
+use super::input::Action;
+use super::model::BodyPart;
+use super::reactions::{HitFlash, HIT_FLASH_SECS};
+use super::species::{Ability, Species};
+use super::status_effects::{StatusEffect, StatusEffects};
+use super::{Player, Team};
+
+const ABILITY_COOLDOWN_SECS: f32 = 3.0;
+
+const INFLATE_SECS: f32 = 3.0;
+/// How much bigger a pufferfish's body gets when it puffs up.
+pub(super) const INFLATE_SCALE: f32 = 1.6;
+const SPINE_POISON_SECS: f32 = 3.0;
+
+const SHOCK_RADIUS: f32 = 250.0;
+const SHOCK_STUN_SECS: f32 = 1.0;
+
+/// How fast a fish has to be swimming forwards to ram anything.
+const RAM_MIN_SPEED: f32 = 5.0;
+/// How squarely a fish has to be pointing at another to ram it, as the cosine of the angle.
+const RAM_AIM: f32 = 0.7;
+const RAM_STUN_SECS: f32 = 0.5;
+
+impl Ability {
+    fn energy_cost(self) -> f32 {
+        match self {
+            Ability::Inflate => 2.0,
+            Ability::Ink => 1.5,
+            Ability::Shock => 2.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub(super) struct AbilityCooldown(Timer);
+
+/// A pufferfish puffed up into a spiny ball.
+#[derive(Component)]
+pub struct Inflated(Timer);
+
+pub(super) fn handle_abilities(
+    mut commands: Commands,
+    time: Res<Time>,
+    ruleset: Res<Ruleset>,
+    actions: Res<Input<Action>>,
+    asset_server: Res<AssetServer>,
+    mut players: Query<
+        (
+            Entity,
+            &Species,
+            &mut Energy,
+            &Transform,
+            &Team,
+            &mut StatusEffects,
+            Option<&mut AbilityCooldown>,
+            Option<&Shielded>,
+        ),
+        (With<Player>, Without<Dead>),
+    >,
+) {
+    let mut shocks = Vec::new();
+
+    for (player, species, mut energy, transform, team, mut status_effects, cooldown, shielded) in
+        players.iter_mut()
+    {
+        let ready = match cooldown {
+            Some(mut cooldown) => {
+                if cooldown.0.tick(time.delta()).finished() {
+                    commands.entity(player).remove::<AbilityCooldown>();
+                }
+                false
+            }
+            None => true,
+        };
+
+        let ability = match species.stats().ability {
+            Some(ability) => ability,
+            None => continue,
+        };
+
+        if !actions.just_pressed(Action::Ability(player))
+            || !ready
+            || shielded.is_some()
+            || !status_effects.can_act()
+            || energy.0 < ability.energy_cost()
+        {
+            continue;
+        }
+
+        energy.0 -= ability.energy_cost();
+        println!("Player {:?} energy: {}", player, energy.0);
+
+        commands
+            .entity(player)
+            .insert(AbilityCooldown(Timer::from_seconds(
+                ABILITY_COOLDOWN_SECS,
+                false,
+            )));
+
+        match ability {
+            Ability::Inflate => {
+                commands
+                    .entity(player)
+                    .insert(Inflated(Timer::from_seconds(INFLATE_SECS, false)));
+                status_effects.apply(StatusEffect::Invulnerability, INFLATE_SECS);
+                status_effects.apply(StatusEffect::Slow, INFLATE_SECS);
+            }
+            Ability::Ink => {
+                spawn_ink_cloud(
+                    &mut commands,
+                    &asset_server,
+                    transform.translation.truncate(),
+                );
+            }
+            Ability::Shock => {
+                shocks.push((player, *team, transform.translation.truncate()));
+
+                spawn_bubble_group(
+                    &mut commands,
+                    &asset_server,
+                    transform.translation,
+                    12,
+                    -SHOCK_RADIUS / 2.0..SHOCK_RADIUS / 2.0,
+                    -SHOCK_RADIUS / 2.0..SHOCK_RADIUS / 2.0,
+                    0.0..0.001,
+                );
+            }
+        }
+    }
+
+    for (eel, team, center) in shocks {
+        for (player, _, _, transform, victim_team, mut status_effects, _, shielded) in
+            players.iter_mut()
+        {
+            if player == eel
+                || (transform.translation.truncate() - center).length() >= SHOCK_RADIUS
+                || (!ruleset.friendly_fire && *victim_team == team)
+                || shielded.is_some()
+                || status_effects.has(StatusEffect::Invulnerability)
+            {
+                continue;
+            }
+
+            status_effects.apply(StatusEffect::Stun, SHOCK_STUN_SECS);
+        }
+    }
+}
+
+/// Puffs pufferfish up and back down again.
+pub(super) fn update_inflation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &Species, &Children, Option<&mut Inflated>), With<Player>>,
+    mut body_parts: Query<(&BodyPart, &mut Transform)>,
+) {
+    const INFLATE_SPEED: f32 = 0.2;
+
+    for (player, species, children, inflated) in players.iter_mut() {
+        let puffed_up = match inflated {
+            Some(mut inflated) => {
+                if inflated.0.tick(time.delta()).finished() {
+                    commands.entity(player).remove::<Inflated>();
+                    false
+                } else {
+                    true
+                }
+            }
+            None => false,
+        };
+
+        let mut target = species.stats().model.body.extend(1.0);
+        if puffed_up {
+            target *= INFLATE_SCALE;
+        }
+
+        for child in children.iter() {
+            if let Ok((BodyPart::Body, mut transform)) = body_parts.get_mut(*child) {
+                let scale = transform.scale;
+                transform.scale = scale + (target - scale) * INFLATE_SPEED;
+            }
+        }
+    }
+}
+
+/// Poisons any fish that bumps into a puffed up pufferfish.
+pub(super) fn prick_with_spines(
+    ruleset: Res<Ruleset>,
+    mut collisions: EventReader<CollisionStarted>,
+    inflated: Query<&Team, With<Inflated>>,
+    mut players: Query<(&Team, &mut StatusEffects), (With<Player>, Without<Shielded>)>,
+) {
+    for collision in collisions.iter() {
+        for (pufferfish, victim) in [(collision.0, collision.1), (collision.1, collision.0)] {
+            let team = match inflated.get(pufferfish) {
+                Ok(team) => *team,
+                Err(_) => continue,
+            };
+
+            if let Ok((victim_team, mut status_effects)) = players.get_mut(victim) {
+                if (ruleset.friendly_fire || *victim_team != team)
+                    && !status_effects.has(StatusEffect::Invulnerability)
+                {
+                    status_effects.apply_from(StatusEffect::Poison, SPINE_POISON_SECS, pufferfish);
+                }
+            }
+        }
+    }
+}
+
+/// Lets fish with a bill hurt whatever they swim head first into.
+pub(super) fn ram(
+    mut commands: Commands,
+    ruleset: Res<Ruleset>,
+    mut collisions: EventReader<CollisionStarted>,
+    mut players: Query<
+        (
+            &Species,
+            &Velocity,
+            &Transform,
+            &Team,
+            &mut HitPoints,
+            &mut StatusEffects,
+            Option<&Shielded>,
+        ),
+        (With<Player>, Without<Dead>),
+    >,
+) {
+    for collision in collisions.iter() {
+        for (attacker, victim) in [(collision.0, collision.1), (collision.1, collision.0)] {
+            let (damage, facing, speed, position, team) = match players.get(attacker) {
+                Ok((species, velocity, transform, team, ..)) => {
+                    let facing = (transform.rotation * Vec3::Y).truncate();
+                    (
+                        species.stats().ram_damage,
+                        facing,
+                        velocity.0.dot(facing),
+                        transform.translation.truncate(),
+                        *team,
+                    )
+                }
+                Err(_) => continue,
+            };
+
+            if damage == 0 || speed < RAM_MIN_SPEED {
+                continue;
+            }
+
+            if let Ok((_, _, transform, victim_team, mut hp, mut status_effects, shielded)) =
+                players.get_mut(victim)
+            {
+                let aim = (transform.translation.truncate() - position)
+                    .normalize_or_zero()
+                    .dot(facing);
+
+                if aim < RAM_AIM
+                    || (!ruleset.friendly_fire && *victim_team == team)
+                    || shielded.is_some()
+                    || status_effects.has(StatusEffect::Invulnerability)
+                    || hp.0 == 0
+                {
+                    continue;
+                }
+
+                hp.0 = hp.0.saturating_sub(damage);
+                println!("{:?} hp: {}", victim, hp.0);
+
+                status_effects.apply(StatusEffect::Stun, RAM_STUN_SECS);
+                commands
+                    .entity(victim)
+                    .insert(HitFlash(Timer::from_seconds(HIT_FLASH_SECS, false)));
+
+                if hp.0 == 0 {
+                    commands
+                        .entity(victim)
+                        .insert(Dead)
+                        .insert(KilledBy(attacker));
+                }
+            }
+        }
+    }
+}
@@ -6,6 +6,7 @@ use bevy::prelude::*;
 use crate::animation::{Animation, AnimationStage};
 use crate::core_components::{AngularVelocity, Dead, Velocity};
 use crate::energy_orbs::{EnergyOrb, RespawnTimer as EnergyOrbRespawnTimer};
+use crate::ink::{hidden_in_ink, InkCloud};
 
 use super::model::BodyPart;
 use super::movement::{PLAYER_MAX_ANGULAR_VELOCITY, PLAYER_MAX_SPEED};
@@ -140,7 +141,15 @@ pub(super) fn animate_eyes(
         (Entity, &Transform, &Children),
         (With<Player>, Without<BodyPart>, Without<Dead>),
     >,
-    orbs: Query<&EnergyOrb, Without<EnergyOrbRespawnTimer>>,
+    orbs: Query<
+        &Transform,
+        (
+            With<EnergyOrb>,
+            Without<EnergyOrbRespawnTimer>,
+            Without<BodyPart>,
+        ),
+    >,
+    clouds: Query<(&Transform, &InkCloud), Without<BodyPart>>,
 ) {
     const FIELD_OF_VIEW: f32 = PI / 4.0;
     const LOOK_SPEED: f32 = 0.2;
@@ -148,11 +157,13 @@ pub(super) fn animate_eyes(
     for (entity, transform, children) in players.iter() {
         let look = (transform.rotation * Vec3::new(0.0, 1.0, 0.0)).truncate();
 
+        // Fish hiding in ink can't be seen, so there's nothing to look at.
         let target = players
             .iter()
             .filter(|(e, _, _)| *e != entity)
             .map(|(_, t, _)| t.translation.truncate())
-            .chain(orbs.iter().map(|o| o.0))
+            .filter(|p| !hidden_in_ink(*p, clouds.iter()))
+            .chain(orbs.iter().map(|t| t.translation.truncate()))
             .map(|p| {
                 let view_vector = p - transform.translation.truncate();
                 (
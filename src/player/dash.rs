@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+
+use crate::background::spawn_bubble_group;
+use crate::core_components::{Energy, Shielded, Velocity};
+
+use super::input::Action;
+use super::status_effects::{StatusEffect, StatusEffects};
+use super::Player;
+
+const DASH_ENERGY_COST: f32 = 1.0;
+const DASH_IMPULSE: f32 = 20.0;
+/// Seconds a dash lasts, during which the fish can outswim its top speed and is invulnerable.
+const DASH_SECS: f32 = 0.3;
+const DASH_COOLDOWN_SECS: f32 = 1.5;
+
+#[derive(Component)]
+pub(super) struct Dashing(Timer);
+
+#[derive(Component)]
+pub(super) struct DashCooldown(Timer);
+
+pub(super) fn handle_dashing(
+    mut commands: Commands,
+    time: Res<Time>,
+    actions: Res<Input<Action>>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Energy,
+            &mut Velocity,
+            &Transform,
+            Option<&mut Dashing>,
+            Option<&mut DashCooldown>,
+            Option<&Shielded>,
+            &mut StatusEffects,
+        ),
+        With<Player>,
+    >,
+) {
+    for (
+        player,
+        mut energy,
+        mut velocity,
+        transform,
+        dashing,
+        cooldown,
+        shielded,
+        mut status_effects,
+    ) in players.iter_mut()
+    {
+        if let Some(mut dashing) = dashing {
+            if dashing.0.tick(time.delta()).finished() {
+                commands.entity(player).remove::<Dashing>();
+            }
+        }
+
+        let ready = match cooldown {
+            Some(mut cooldown) => {
+                if cooldown.0.tick(time.delta()).finished() {
+                    commands.entity(player).remove::<DashCooldown>();
+                }
+                false
+            }
+            None => true,
+        };
+
+        if actions.just_pressed(Action::Dash(player))
+            && ready
+            && shielded.is_none()
+            && status_effects.can_act()
+            && energy.0 >= DASH_ENERGY_COST
+        {
+            energy.0 -= DASH_ENERGY_COST;
+            println!("Player {:?} energy: {}", player, energy.0);
+
+            velocity.0 += (transform.rotation * Vec3::new(0.0, DASH_IMPULSE, 0.0)).truncate();
+            status_effects.apply(StatusEffect::Invulnerability, DASH_SECS);
+
+            commands
+                .entity(player)
+                .insert(Dashing(Timer::from_seconds(DASH_SECS, false)))
+                .insert(DashCooldown(Timer::from_seconds(DASH_COOLDOWN_SECS, false)));
+        }
+    }
+}
+
+/// Leaves a stream of bubbles behind dashing fish.
+pub(super) fn dash_trail(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    players: Query<&Transform, (With<Player>, With<Dashing>)>,
+) {
+    for transform in players.iter() {
+        let tail = transform.translation
+            - transform.rotation * Vec3::new(0.0, 128.0, 0.0) * transform.scale.z;
+
+        spawn_bubble_group(
+            &mut commands,
+            &asset_server,
+            tail,
+            1,
+            -10.0..10.0,
+            -10.0..10.0,
+            0.0..0.001,
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 
 use super::Player;
@@ -9,19 +11,30 @@ pub(super) enum Action {
     TurnRight(Entity),
     Shield(Entity),
     Shoot(Entity),
+    Dash(Entity),
+    Ability(Entity),
 }
 
-#[derive(Clone, Component, Copy)]
+/// Longest gap between two presses of forward for them to count as a dash.
+const DOUBLE_TAP_SECS: f64 = 0.25;
+
+#[derive(Clone, Component, Copy, PartialEq)]
 pub struct KeyMap {
     pub forward: KeyCode,
     pub left: KeyCode,
     pub right: KeyCode,
     pub shoot: KeyCode,
+    /// A key of its own for dashing.  Without one, double-tapping forward dashes.
+    pub dash: Option<KeyCode>,
+    /// Uses the fish's species ability, if it has one.
+    pub ability: KeyCode,
 }
 
 pub(super) fn gather_player_input(
     mut actions: ResMut<Input<Action>>,
     keyboard: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut last_forward: Local<HashMap<Entity, f64>>,
     players: Query<(Entity, &KeyMap), With<Player>>,
 ) {
     actions.clear();
@@ -31,10 +44,37 @@ pub(super) fn gather_player_input(
 
         if keyboard.just_pressed(keymap.forward) {
             actions.press(Action::MoveForward(player));
+
+            let now = time.seconds_since_startup();
+            let double_tap = matches!(
+                last_forward.insert(player, now),
+                Some(last) if now - last <= DOUBLE_TAP_SECS
+            );
+
+            // Dashing is a one-off, so it's let go again straight away.
+            if double_tap && keymap.dash.is_none() {
+                actions.press(Action::Dash(player));
+                actions.release(Action::Dash(player));
+                last_forward.remove(&player);
+            }
         } else if keyboard.just_released(keymap.forward) {
             actions.release(Action::MoveForward(player));
         }
 
+        if keyboard.just_pressed(keymap.ability) {
+            actions.press(Action::Ability(player));
+        } else if keyboard.just_released(keymap.ability) {
+            actions.release(Action::Ability(player));
+        }
+
+        if let Some(dash) = keymap.dash {
+            if keyboard.just_pressed(dash) {
+                actions.press(Action::Dash(player));
+            } else if keyboard.just_released(dash) {
+                actions.release(Action::Dash(player));
+            }
+        }
+
         if keyboard.just_pressed(keymap.shoot) {
             actions.press(Action::Shoot(player));
         } else if keyboard.just_released(keymap.shoot) {
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::background::spawn_bubble_group;
+use crate::State;
+
+use super::model::build_model;
+use super::species::Species;
+use super::{PlayerConfiguration, PLAYER_START_ANGLES, PLAYER_START_POSITIONS};
+
+/// Starts the match straight away, for when not every configured player is at the keyboard.
+const START_KEY: KeyCode = KeyCode::Return;
+
+/// A fish shown in the lobby so its player can see which species they've picked.
+#[derive(Clone, Component, Copy)]
+pub(super) struct Preview {
+    /// Which slot in the player configuration it belongs to.
+    slot: usize,
+    position: Vec2,
+    rotation: Quat,
+}
+
+/// The slots of every player who has settled on a species.
+#[derive(Default)]
+pub(super) struct Ready(HashSet<usize>);
+
+pub(super) fn enter_lobby(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_config: Res<PlayerConfiguration>,
+    mut ready: ResMut<Ready>,
+) {
+    ready.0.clear();
+
+    let players = player_config
+        .0
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, p)| p.as_ref().map(|p| (slot, p)));
+
+    for (i, (slot, configuration)) in players.enumerate() {
+        let position = Vec2::new(PLAYER_START_POSITIONS[i].0, PLAYER_START_POSITIONS[i].1);
+        let rotation = Quat::from_rotation_z(PLAYER_START_ANGLES[i]);
+
+        spawn_preview(
+            &mut commands,
+            &asset_server,
+            Preview {
+                slot,
+                position,
+                rotation,
+            },
+            configuration.species,
+            configuration.color.0,
+        );
+    }
+
+    println!("Pick a species with left and right, then shoot when ready");
+}
+
+fn spawn_preview(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    preview: Preview,
+    species: Species,
+    color: Color,
+) {
+    let (position, rotation) = (preview.position, preview.rotation);
+    let mut entity = commands.spawn();
+
+    build_model(
+        &mut entity,
+        asset_server,
+        &species.stats().model,
+        position,
+        rotation,
+        color,
+    );
+
+    entity.insert(preview);
+}
+
+/// Lets each player flip through the species with their turning keys and ready up with their
+/// shoot key.  The match starts once everyone is ready.
+pub(super) fn pick_species(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keyboard: Res<Input<KeyCode>>,
+    mut state: ResMut<bevy::ecs::schedule::State<State>>,
+    mut player_config: ResMut<PlayerConfiguration>,
+    mut ready: ResMut<Ready>,
+    previews: Query<(Entity, &Preview)>,
+) {
+    for (entity, preview) in previews.iter() {
+        let configuration = match player_config.0[preview.slot].as_mut() {
+            Some(configuration) => configuration,
+            None => continue,
+        };
+
+        let keymap = configuration.keymap;
+
+        if keyboard.just_pressed(keymap.shoot) {
+            if ready.0.insert(preview.slot) {
+                println!("Player {} is ready", preview.slot + 1);
+
+                spawn_bubble_group(
+                    &mut commands,
+                    &asset_server,
+                    preview.position.extend(1.0),
+                    8,
+                    -60.0..60.0,
+                    -60.0..60.0,
+                    0.0..0.001,
+                );
+            } else {
+                ready.0.remove(&preview.slot);
+                println!("Player {} is picking again", preview.slot + 1);
+            }
+        }
+
+        // A ready player's pick is locked in.
+        let step = match (
+            keyboard.just_pressed(keymap.left),
+            keyboard.just_pressed(keymap.right),
+        ) {
+            _ if ready.0.contains(&preview.slot) => continue,
+            (true, false) => Species::ALL.len() - 1,
+            (false, true) => 1,
+            _ => continue,
+        };
+
+        let current = Species::ALL
+            .iter()
+            .position(|s| *s == configuration.species)
+            .unwrap_or(0);
+        configuration.species = Species::ALL[(current + step) % Species::ALL.len()];
+        println!(
+            "Player {} picked {:?}",
+            preview.slot + 1,
+            configuration.species
+        );
+
+        let (species, color) = (configuration.species, configuration.color.0);
+
+        commands.entity(entity).despawn_recursive();
+        spawn_preview(&mut commands, &asset_server, *preview, species, color);
+    }
+
+    let everyone_ready = previews.iter().all(|(_, p)| ready.0.contains(&p.slot));
+
+    if (everyone_ready && !previews.is_empty()) || keyboard.just_pressed(START_KEY) {
+        let _ = state.set(State::Game);
+    }
+}
+
+pub(super) fn leave_lobby(mut commands: Commands, previews: Query<Entity, With<Preview>>) {
+    for entity in previews.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
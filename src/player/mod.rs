@@ -4,29 +4,56 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
+use crate::collision::{CollisionLayer, CollisionLayers};
 use crate::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
 use crate::core_components::{
     AngularVelocity, CollisionCircle, Energy, HitPoints, Lives, Velocity,
 };
 use crate::State;
 
+pub use self::abilities::Inflated;
 pub use self::input::KeyMap;
 pub use self::model::PLAYER_SCALE;
+pub use self::power_ups::{Magnet, PowerUp};
+pub use self::projectiles::spawn_projectile;
 pub use self::shield::PLAYER_SHIELD_SCALE;
+pub use self::species::Species;
+pub use self::weapons::Weapon;
 
+use self::abilities::{handle_abilities, prick_with_spines, ram, update_inflation};
 use self::animation::{animate_eyes, animate_swimming, SwimmingAnimation};
+use self::dash::{dash_trail, handle_dashing};
 use self::input::{gather_player_input, Action};
+use self::lobby::{enter_lobby, leave_lobby, pick_species, Ready};
 use self::model::build_model;
 use self::movement::{handle_collision, handle_movement, move_players};
-use self::projectiles::{handle_projectiles, handle_shooting};
+use self::power_ups::{
+    expire_power_up, update_auras, DoubleDamage, FreeShield, RapidFire, SpeedBoost,
+};
+use self::predation::{handle_bites, Bite};
+use self::projectiles::{
+    cull_projectiles, handle_projectiles, handle_shooting, move_projectiles, shake_off_projectiles,
+    steer_homing_projectiles,
+};
+use self::reactions::{heal_injuries, tint_body_parts};
 use self::shield::handle_shielding;
+use self::status_effects::{update_status_effects, StatusEffects};
 
+mod abilities;
 mod animation;
+mod dash;
 mod input;
+mod lobby;
 mod model;
 mod movement;
+mod power_ups;
+mod predation;
 mod projectiles;
+mod reactions;
 mod shield;
+mod species;
+mod status_effects;
+mod weapons;
 
 pub struct PlayerPlugin;
 
@@ -34,6 +61,11 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(PlayerConfiguration(vec![None; 4]))
             .insert_resource(Input::<Action>::default())
+            .insert_resource(Ready::default())
+            .add_event::<Bite>()
+            .add_system_set(SystemSet::on_enter(State::Menu).with_system(enter_lobby))
+            .add_system_set(SystemSet::on_update(State::Menu).with_system(pick_species))
+            .add_system_set(SystemSet::on_exit(State::Menu).with_system(leave_lobby))
             .add_system_set(SystemSet::on_enter(State::Game).with_system(create_players))
             .add_system_set(
                 SystemSet::on_update(State::Game)
@@ -41,6 +73,17 @@ impl Plugin for PlayerPlugin {
                     .with_system(handle_movement.label("input"))
                     .with_system(handle_shielding.label("input"))
                     .with_system(handle_shooting.label("input"))
+                    .with_system(handle_dashing.label("input"))
+                    .with_system(handle_abilities.label("input"))
+                    .with_system(
+                        update_inflation
+                            .label("update_inflation")
+                            .after("animation"),
+                    )
+                    .with_system(prick_with_spines.after("collision_detection"))
+                    .with_system(ram.after("collision_detection"))
+                    .with_system(handle_bites.after("handle_collision"))
+                    .with_system(dash_trail.after("physics"))
                     .with_system(
                         move_players
                             .label("move_players")
@@ -50,26 +93,58 @@ impl Plugin for PlayerPlugin {
                     .with_system(
                         handle_collision
                             .label("handle_collision")
+                            .after("collision_detection"),
+                    )
+                    .with_system(
+                        steer_homing_projectiles
                             .label("physics")
-                            .after("move_players"),
+                            .after("input")
+                            .before("move_projectiles"),
                     )
                     .with_system(
-                        handle_projectiles
+                        move_projectiles
+                            .label("move_projectiles")
                             .label("physics")
-                            .after("handle_collision"),
+                            .after("input"),
                     )
+                    .with_system(cull_projectiles.label("physics").after("move_projectiles"))
+                    .with_system(
+                        handle_projectiles
+                            .label("handle_projectiles")
+                            .after("collision_detection"),
+                    )
+                    .with_system(shake_off_projectiles.after("handle_projectiles"))
                     .with_system(
                         animate_swimming
                             .label("animate_swimming")
                             .label("animation")
-                            .after("physics"),
+                            .after("physics")
+                            .after("handle_collision"),
                     )
                     .with_system(animate_eyes.label("animation").after("animate_swimming"))
-                    .with_system(hide_invisible_players),
+                    .with_system(hide_invisible_players)
+                    .with_system(expire_power_up::<SpeedBoost>.after("input"))
+                    .with_system(expire_power_up::<RapidFire>.after("input"))
+                    .with_system(expire_power_up::<FreeShield>.after("input"))
+                    .with_system(expire_power_up::<DoubleDamage>.after("input"))
+                    .with_system(expire_power_up::<Magnet>.after("input"))
+                    .with_system(update_auras)
+                    .with_system(update_status_effects.before("input"))
+                    .with_system(heal_injuries.before("input"))
+                    .with_system(tint_body_parts.after("collision_detection")),
             );
     }
 }
 
+const PLAYER_START_POSITIONS: [(f32, f32); 4] = [
+    (LOGICAL_WIDTH as f32 * 0.35, LOGICAL_HEIGHT as f32 * 0.70),
+    (LOGICAL_WIDTH as f32 * 0.65, LOGICAL_HEIGHT as f32 * 0.70),
+    (LOGICAL_WIDTH as f32 * 0.35, LOGICAL_HEIGHT as f32 * 0.30),
+    (LOGICAL_WIDTH as f32 * 0.65, LOGICAL_HEIGHT as f32 * 0.30),
+];
+
+const PLAYER_START_ANGLES: [f32; 4] = [PI / 4.0, -PI / 4.0, 3.0 * PI / 4.0, -3.0 * PI / 4.0];
+
 #[derive(Clone, Component)]
 pub struct Player;
 
@@ -81,16 +156,41 @@ pub struct PlayerConfigurationBundle {
     pub color: PlayerColor,
     pub hp: HitPoints,
     pub lives: Lives,
+    pub weapon: Weapon,
+    pub team: Team,
+    pub species: Species,
 }
 
 #[derive(Clone, Component)]
 pub struct PlayerColor(pub Color);
 
+/// Which side a player is on.  Give every player their own team for a free-for-all.
+#[derive(Clone, Component, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Team(pub usize);
+
+impl Team {
+    pub fn color(self) -> Color {
+        const TEAM_COLORS: [Color; 4] = [
+            Color::rgb(1.0, 0.3, 0.3),
+            Color::rgb(0.3, 0.5, 1.0),
+            Color::rgb(0.3, 1.0, 0.4),
+            Color::rgb(1.0, 0.85, 0.2),
+        ];
+
+        TEAM_COLORS[self.0 % TEAM_COLORS.len()]
+    }
+}
+
+/// A glow beneath a fish showing which team it's on.
+#[derive(Component)]
+struct TeamIndicator;
+
 #[derive(Bundle, Default)]
 struct PlayerObjectBundle {
     velocity: Velocity,
     angular_velocity: AngularVelocity,
     energy: Energy,
+    status_effects: StatusEffects,
 }
 
 fn create_players(
@@ -98,37 +198,87 @@ fn create_players(
     player_config: Res<PlayerConfiguration>,
     asset_server: Res<AssetServer>,
 ) {
-    const PLAYER_START_POSITIONS: [(f32, f32); 4] = [
-        (LOGICAL_WIDTH as f32 * 0.35, LOGICAL_HEIGHT as f32 * 0.70),
-        (LOGICAL_WIDTH as f32 * 0.65, LOGICAL_HEIGHT as f32 * 0.70),
-        (LOGICAL_WIDTH as f32 * 0.35, LOGICAL_HEIGHT as f32 * 0.30),
-        (LOGICAL_WIDTH as f32 * 0.65, LOGICAL_HEIGHT as f32 * 0.30),
-    ];
-
-    const PLAYER_START_ANGLES: [f32; 4] = [PI / 4.0, -PI / 4.0, 3.0 * PI / 4.0, -3.0 * PI / 4.0];
-
-    for (i, player_configuration) in player_config.0.iter().cloned().flatten().enumerate() {
-        let mut player = commands.spawn();
-
-        player
-            .insert(Player)
-            .insert_bundle(player_configuration.clone())
-            .insert_bundle(PlayerObjectBundle::default())
-            .insert(CollisionCircle {
-                radius: 128.0 * PLAYER_SCALE,
-            })
-            .insert(SwimmingAnimation(Timer::from_seconds(0.333, true)));
-
-        build_model(
-            &mut player,
+    for (i, player_configuration) in player_config.0.iter().flatten().enumerate() {
+        spawn_player(
+            &mut commands,
             &asset_server,
+            &player_config,
+            player_configuration,
             Vec2::new(PLAYER_START_POSITIONS[i].0, PLAYER_START_POSITIONS[i].1),
             Quat::from_rotation_z(PLAYER_START_ANGLES[i]),
-            player_configuration.color.0,
         );
     }
 }
 
+/// Spawns a fresh fish for one of the configured players.
+pub fn spawn_player(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    player_config: &PlayerConfiguration,
+    player_configuration: &PlayerConfigurationBundle,
+    position: Vec2,
+    rotation: Quat,
+) {
+    const TEAM_INDICATOR_SCALE: f32 = 2.0;
+
+    let players = player_config.0.iter().flatten().collect::<Vec<_>>();
+
+    // Teams are only worth pointing out when someone has a teammate.
+    let show_teams = players
+        .iter()
+        .any(|a| players.iter().filter(|b| b.team == a.team).count() > 1);
+
+    let mut player = commands.spawn();
+
+    player
+        .insert(Player)
+        .insert_bundle(player_configuration.clone())
+        .insert_bundle(PlayerObjectBundle::default())
+        .insert(CollisionCircle {
+            radius: 128.0 * PLAYER_SCALE * player_configuration.species.collision_scale(false),
+        })
+        .insert(
+            CollisionLayers::new(CollisionLayer::Player)
+                .with_filter(CollisionLayer::Player)
+                .with_filter(CollisionLayer::Projectile)
+                .with_filter(CollisionLayer::Orb)
+                .with_filter(CollisionLayer::Pickup),
+        )
+        .insert(SwimmingAnimation(Timer::from_seconds(0.333, true)));
+
+    let species = player_configuration.species.stats();
+
+    // Tougher species get more hit points than they were configured with, frailer ones fewer.
+    let hp = (player_configuration.hp.0 as f32 * species.hp).round() as u32;
+    player.insert(HitPoints(hp.max(1)));
+
+    build_model(
+        &mut player,
+        asset_server,
+        &species.model,
+        position,
+        rotation,
+        player_configuration.color.0,
+    );
+
+    if show_teams {
+        let mut color = player_configuration.team.color();
+        color.set_a(0.4);
+
+        player.with_children(|player| {
+            player
+                .spawn_bundle(SpriteBundle {
+                    texture: asset_server.load("images/orb.png"),
+                    transform: Transform::from_scale(Vec3::splat(TEAM_INDICATOR_SCALE))
+                        .with_translation(Vec3::new(0.0, 0.0, -2.0)),
+                    sprite: Sprite { color, ..default() },
+                    ..default()
+                })
+                .insert(TeamIndicator);
+        });
+    }
+}
+
 fn hide_invisible_players(
     mut body_parts: Query<(&mut Visibility, Option<&Children>), Without<Player>>,
     players: Query<(&Visibility, &Children), With<Player>>,
@@ -1,8 +1,15 @@
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 
+use crate::core_components::Projectile;
+
 pub const PLAYER_SCALE: f32 = 0.4;
 
+/// How big a fish currently is, relative to a fresh one.
+pub(super) fn relative_size(transform: &Transform) -> f32 {
+    transform.scale.z / PLAYER_SCALE
+}
+
 #[derive(Clone, Component, Copy, PartialEq)]
 pub(super) enum BodyPart {
     Head,
@@ -14,9 +21,97 @@ pub(super) enum BodyPart {
     LeftEye,
 }
 
+/// How a species' fish is put together.  Scales are relative to each part's sprite.
+pub(super) struct ModelDefinition {
+    pub(super) head: Vec2,
+    pub(super) body: Vec2,
+    pub(super) tail: Vec2,
+    pub(super) fins: Vec2,
+    /// Stretches or squashes the fish from nose to tail.
+    pub(super) length: f32,
+    /// Gives the fish a long, pointed bill sticking out in front.
+    pub(super) bill: bool,
+}
+
+impl Default for ModelDefinition {
+    fn default() -> Self {
+        Self {
+            head: Vec2::ONE,
+            body: Vec2::ONE,
+            tail: Vec2::ONE,
+            fins: Vec2::ONE,
+            length: 1.0,
+            bill: false,
+        }
+    }
+}
+
+/// The part of the fish a projectile has to touch to hit it there, as a radius in the part's
+/// own pixels.
+#[derive(Component)]
+pub(super) struct Hitbox(pub(super) f32);
+
+pub(super) type BodyPartQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static BodyPart,
+        &'static Transform,
+        Option<&'static Hitbox>,
+        Option<&'static Children>,
+    ),
+    Without<Projectile>,
+>;
+
+/// Finds which part of a fish with the given `transform` and `children` a point touches, and
+/// where that part is in the world.  Where hitboxes overlap, the one the point is deepest in
+/// wins.
+pub(super) fn locate_hit(
+    transform: &Transform,
+    children: &Children,
+    point: Vec2,
+    body_parts: &BodyPartQuery,
+) -> Option<(Entity, BodyPart, Transform)> {
+    fn search(
+        transform: &Transform,
+        children: &Children,
+        point: Vec2,
+        body_parts: &BodyPartQuery,
+        best: &mut Option<(f32, (Entity, BodyPart, Transform))>,
+    ) {
+        for (part, body_part, local, hitbox, part_children) in
+            children.iter().filter_map(|c| body_parts.get(*c).ok())
+        {
+            // Body parts swing about with the animation, so work out where they are from the
+            // fish's current transform rather than last frame's global one.
+            let world = transform.mul_transform(*local);
+
+            if let Some(hitbox) = hitbox {
+                let radius = hitbox.0 * world.scale.truncate().max_element();
+                let depth = radius - (world.translation.truncate() - point).length();
+
+                if depth > 0.0 && best.map_or(true, |(d, _)| depth > d) {
+                    *best = Some((depth, (part, *body_part, world)));
+                }
+            }
+
+            if let Some(part_children) = part_children {
+                search(&world, part_children, point, body_parts, best);
+            }
+        }
+    }
+
+    let mut best = None;
+    search(transform, children, point, body_parts, &mut best);
+
+    best.map(|(_, hit)| hit)
+}
+
 pub(super) fn build_model(
     commands: &mut EntityCommands,
     asset_server: &Res<AssetServer>,
+    model: &ModelDefinition,
     start_position: Vec2,
     start_rotation: Quat,
     color: Color,
@@ -32,25 +127,30 @@ pub(super) fn build_model(
         .with_children(|root| {
             root.spawn_bundle(SpriteBundle {
                 texture: asset_server.load("images/player/head.png"),
-                transform: Transform::from_translation(Vec3::new(0.0, 20.0, 1.0)),
+                transform: Transform::from_translation(Vec3::new(0.0, 20.0 * model.length, 1.0))
+                    .with_scale(model.head.extend(1.0)),
                 sprite: Sprite { color, ..default() },
                 ..default()
             })
             .insert(BodyPart::Head)
+            .insert(Hitbox(100.0))
             .with_children(|head| {
                 head.spawn_bundle(SpriteBundle {
                     texture: asset_server.load("images/player/fin.png"),
                     transform: Transform::from_translation(Vec3::new(115.0, 0.0, 1.0))
-                        .with_rotation(Quat::from_rotation_z(0.375)),
+                        .with_rotation(Quat::from_rotation_z(0.375))
+                        .with_scale(model.fins.extend(1.0)),
                     sprite: Sprite { color, ..default() },
                     ..default()
                 })
-                .insert(BodyPart::RightFin);
+                .insert(BodyPart::RightFin)
+                .insert(Hitbox(50.0));
 
                 head.spawn_bundle(SpriteBundle {
                     texture: asset_server.load("images/player/fin.png"),
                     transform: Transform::from_translation(Vec3::new(-115.0, 0.0, 1.0))
-                        .with_rotation(Quat::from_rotation_z(-0.375)),
+                        .with_rotation(Quat::from_rotation_z(-0.375))
+                        .with_scale(model.fins.extend(1.0)),
                     sprite: Sprite {
                         color,
                         flip_x: true,
@@ -58,7 +158,8 @@ pub(super) fn build_model(
                     },
                     ..default()
                 })
-                .insert(BodyPart::LeftFin);
+                .insert(BodyPart::LeftFin)
+                .insert(Hitbox(50.0));
 
                 head.spawn_bundle(SpriteBundle {
                     texture: asset_server.load("images/player/eye-open.png"),
@@ -87,6 +188,16 @@ pub(super) fn build_model(
                     ..default()
                 });
 
+                if model.bill {
+                    head.spawn_bundle(SpriteBundle {
+                        texture: asset_server.load("images/player/bill.png"),
+                        transform: Transform::from_translation(Vec3::new(0.0, 190.0, 0.5))
+                            .with_scale(Vec3::new(0.6, 0.8, 1.0)),
+                        sprite: Sprite { color, ..default() },
+                        ..default()
+                    });
+                }
+
                 head.spawn_bundle(SpriteBundle {
                     texture: asset_server.load("images/player/dorsal_fin.png"),
                     transform: Transform::from_translation(Vec3::new(0.0, 0.0, 2.0)),
@@ -97,18 +208,22 @@ pub(super) fn build_model(
 
             root.spawn_bundle(SpriteBundle {
                 texture: asset_server.load("images/player/body.png"),
-                transform: Transform::from_translation(Vec3::new(0.0, -60.0, 2.0)),
+                transform: Transform::from_translation(Vec3::new(0.0, -60.0 * model.length, 2.0))
+                    .with_scale(model.body.extend(1.0)),
                 sprite: Sprite { color, ..default() },
                 ..default()
             })
-            .insert(BodyPart::Body);
+            .insert(BodyPart::Body)
+            .insert(Hitbox(80.0));
 
             root.spawn_bundle(SpriteBundle {
                 texture: asset_server.load("images/player/tail.png"),
-                transform: Transform::from_translation(Vec3::new(0.0, -105.0, 3.0)),
+                transform: Transform::from_translation(Vec3::new(0.0, -105.0 * model.length, 3.0))
+                    .with_scale(model.tail.extend(1.0)),
                 sprite: Sprite { color, ..default() },
                 ..default()
             })
-            .insert(BodyPart::Tail);
+            .insert(BodyPart::Tail)
+            .insert(Hitbox(60.0));
         });
 }
@@ -1,9 +1,21 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 
-use crate::configuration::{LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::collision::{separation, ArenaBounds, Contacts};
+use crate::configuration::Ruleset;
 use crate::core_components::{AngularVelocity, CollisionCircle, Shielded, Velocity};
+use crate::currents::current_at;
 
+use super::dash::Dashing;
 use super::input::Action;
+use super::model::relative_size;
+use super::power_ups::{SpeedBoost, SPEED_BOOST_MULTIPLIER};
+use super::predation::Bite;
+use super::projectiles::Stuck;
+use super::reactions::{InjuredFin, InjuredTail, INJURED_FIN_ACCELERATION, INJURED_TAIL_TURNING};
+use super::species::Species;
+use super::status_effects::StatusEffects;
 use super::Player;
 
 pub(super) const PLAYER_MAX_SPEED: f32 = 8.0;
@@ -11,13 +23,19 @@ pub(super) const PLAYER_MAX_ANGULAR_VELOCITY: f32 = 0.075;
 
 pub(super) fn handle_movement(
     actions: Res<Input<Action>>,
+    ruleset: Res<Ruleset>,
     mut players: Query<
         (
             Entity,
             &mut Velocity,
             &mut AngularVelocity,
             &Transform,
+            Option<&SpeedBoost>,
             Option<&Shielded>,
+            &StatusEffects,
+            Option<&InjuredTail>,
+            Option<&InjuredFin>,
+            &Species,
         ),
         With<Player>,
     >,
@@ -25,28 +43,76 @@ pub(super) fn handle_movement(
     const PLAYER_ACCELERATION: f32 = 0.3;
     const PLAYER_ANGULAR_ACCELERATION: f32 = 0.015;
 
-    for (player, mut velocity, mut angular_velocity, transform, shielded) in players.iter_mut() {
+    for (
+        player,
+        mut velocity,
+        mut angular_velocity,
+        transform,
+        speed_boost,
+        shielded,
+        status_effects,
+        injured_tail,
+        injured_fin,
+        species,
+    ) in players.iter_mut()
+    {
+        if !status_effects.can_act() {
+            continue;
+        }
+
+        let size = relative_size(transform);
+        let species = species.stats();
+
         if actions.pressed(Action::MoveForward(player)) && shielded.is_none() {
-            velocity.0 += (transform.rotation * Vec3::new(0.0, PLAYER_ACCELERATION, 0.0)).truncate()
+            let mut acceleration = if speed_boost.is_some() {
+                PLAYER_ACCELERATION * SPEED_BOOST_MULTIPLIER
+            } else {
+                PLAYER_ACCELERATION
+            };
+
+            if injured_fin.is_some() {
+                acceleration *= INJURED_FIN_ACCELERATION;
+            }
+
+            acceleration *= ruleset.size_stats.acceleration(size);
+            acceleration *= status_effects.speed_multiplier();
+            acceleration *= species.acceleration;
+
+            velocity.0 += (transform.rotation * Vec3::new(0.0, acceleration, 0.0)).truncate()
         }
 
+        let mut angular_acceleration = if injured_tail.is_some() {
+            PLAYER_ANGULAR_ACCELERATION * INJURED_TAIL_TURNING
+        } else {
+            PLAYER_ANGULAR_ACCELERATION
+        };
+
+        angular_acceleration *= ruleset.size_stats.turning(size) * species.turning;
+
         if actions.pressed(Action::TurnLeft(player)) {
-            angular_velocity.0 += PLAYER_ANGULAR_ACCELERATION;
+            angular_velocity.0 += angular_acceleration;
         }
 
         if actions.pressed(Action::TurnRight(player)) {
-            angular_velocity.0 -= PLAYER_ANGULAR_ACCELERATION;
+            angular_velocity.0 -= angular_acceleration;
         }
     }
 }
 
 pub(super) fn move_players(
+    ruleset: Res<Ruleset>,
+    stuck: Query<&Stuck>,
     mut players: Query<
         (
+            Entity,
             &mut Velocity,
             &mut AngularVelocity,
             &mut Transform,
+            Option<&SpeedBoost>,
             Option<&Shielded>,
+            Option<&Dashing>,
+            &StatusEffects,
+            &Species,
         ),
         With<Player>,
     >,
@@ -54,21 +120,55 @@ pub(super) fn move_players(
     const PLAYER_DECELERATION: f32 = 0.02;
     const PLAYER_ANGULAR_DECELERATION: f32 = 0.2;
     const PLAYER_SHIELD_BRAKE: f32 = 0.1;
+    /// How much each projectile stuck in a fish takes off its top speed.
+    const STUCK_PROJECTILE_DRAG: f32 = 0.05;
+
+    let mut stuck_counts = HashMap::new();
+    for stuck in stuck.iter() {
+        *stuck_counts.entry(stuck.fish).or_insert(0) += 1;
+    }
+
+    for (
+        player,
+        mut velocity,
+        mut angular_velocity,
+        mut transform,
+        speed_boost,
+        shielded,
+        dashing,
+        status_effects,
+        species,
+    ) in players.iter_mut()
+    {
+        let mut max_speed = if speed_boost.is_some() {
+            PLAYER_MAX_SPEED * SPEED_BOOST_MULTIPLIER
+        } else {
+            PLAYER_MAX_SPEED
+        };
 
-    for (mut velocity, mut angular_velocity, mut transform, shielded) in players.iter_mut() {
-        if velocity.0.length() > PLAYER_MAX_SPEED {
-            velocity.0 = velocity.0.normalize() * PLAYER_MAX_SPEED;
+        let stuck_count = stuck_counts.get(&player).copied().unwrap_or(0);
+        max_speed *= (1.0 - STUCK_PROJECTILE_DRAG).powi(stuck_count);
+        max_speed *= status_effects.speed_multiplier();
+        let species = species.stats();
+        max_speed *= species.speed;
+
+        // A dash is allowed to burst past the usual top speed.
+        if velocity.0.length() > max_speed && dashing.is_none() {
+            velocity.0 = velocity.0.normalize() * max_speed;
         }
 
+        let max_angular_velocity = PLAYER_MAX_ANGULAR_VELOCITY * species.turning;
         angular_velocity.0 = angular_velocity
             .0
-            .min(PLAYER_MAX_ANGULAR_VELOCITY)
-            .max(-PLAYER_MAX_ANGULAR_VELOCITY);
+            .min(max_angular_velocity)
+            .max(-max_angular_velocity);
 
         transform.translation += velocity.0.extend(0.0);
         transform.rotation *= Quat::from_rotation_z(angular_velocity.0);
 
-        velocity.0 *= 1.0 - PLAYER_DECELERATION;
+        // Drag is against the water, so a fish left alone ends up moving with the current.
+        let current = current_at(&ruleset, transform.translation.truncate());
+        velocity.0 = current + (velocity.0 - current) * (1.0 - PLAYER_DECELERATION);
         if shielded.is_some() {
             velocity.0 *= 1.0 - PLAYER_SHIELD_BRAKE;
         }
@@ -79,61 +179,144 @@ pub(super) fn move_players(
 
 // XXX When https://github.com/bevyengine/bevy/issues/3651 gets fixed, change this to a With<Player> query.
 pub(super) fn handle_collision(
-    mut players: Query<(&Player, &mut Velocity, &mut Transform, &CollisionCircle)>,
+    bounds: Res<ArenaBounds>,
+    ruleset: Res<Ruleset>,
+    contacts: Res<Contacts>,
+    mut bites: EventWriter<Bite>,
+    mut players: Query<(
+        Entity,
+        &Player,
+        &mut Velocity,
+        &mut Transform,
+        &CollisionCircle,
+        Option<&Shielded>,
+        &Species,
+    )>,
 ) {
     const COLLISION_ITERATIONS: usize = 10;
     const COLLISION_MARGIN: f32 = 0.1;
+    /// How much heavier a fish is behind its shield, for bashing others about.
+    const SHIELD_MASS_MULTIPLIER: f32 = 2.0;
+    /// How squarely a fish has to be facing another to bite it, as the cosine of the angle.
+    const BITE_AIM: f32 = 0.7;
+
+    // A fish grown big on orbs is a lot harder to push around.
+    let mass = |transform: &Transform, shielded: Option<&Shielded>, species: &Species| {
+        let mass = ruleset.size_stats.mass(relative_size(transform)) * species.stats().mass;
+
+        if shielded.is_some() {
+            mass * SHIELD_MASS_MULTIPLIER
+        } else {
+            mass
+        }
+    };
+
+    // A fish can bite another that's enough smaller than it by swimming into it mouth first.
+    let can_bite = |predator: &Transform, prey: &Transform| match &ruleset.predation {
+        Some(predation) => {
+            let facing = (predator.rotation * Vec3::Y).truncate();
+            let aim = (prey.translation - predator.translation)
+                .truncate()
+                .normalize_or_zero()
+                .dot(facing);
+
+            relative_size(predator) >= relative_size(prey) * predation.size_ratio && aim >= BITE_AIM
+        }
+        None => false,
+    };
 
     let mut found_collision = false;
 
-    'check_all: for _ in 0..COLLISION_ITERATIONS {
+    'check_all: for iteration in 0..COLLISION_ITERATIONS {
         // Collide players against others
         let mut combinations = players.iter_combinations_mut();
         while let Some(
-            [(_, _, mut transform_a, collision_a), (_, _, mut transform_b, collision_b)],
+            [(a, _, mut velocity_a, mut transform_a, collision_a, shielded_a, species_a), (b, _, mut velocity_b, mut transform_b, collision_b, shielded_b, species_b)],
         ) = combinations.fetch_next()
         {
+            // Pushing fish apart can shove them into others, but those are left for the next
+            // detection pass to pick up.
+            if !contacts.contains(a, b) {
+                continue;
+            }
+
             let vector_between = transform_b.translation - transform_a.translation;
-            let distance = vector_between.length();
+            let gap = separation(
+                transform_a.translation.truncate(),
+                collision_a,
+                transform_b.translation.truncate(),
+                collision_b,
+            );
 
-            if distance < collision_a.radius + collision_b.radius {
+            if gap < 0.0 {
                 found_collision = true;
             } else {
                 continue;
             }
 
-            let correction =
-                (distance - collision_a.radius - collision_b.radius) / 2.0 - COLLISION_MARGIN;
+            // Only bite on first contact, before the fish are pushed apart.
+            if iteration == 0 {
+                if can_bite(&transform_a, &transform_b) {
+                    bites.send(Bite {
+                        predator: a,
+                        prey: b,
+                    });
+                } else if can_bite(&transform_b, &transform_a) {
+                    bites.send(Bite {
+                        predator: b,
+                        prey: a,
+                    });
+                }
+            }
+
+            let inverse_mass_a = 1.0 / mass(&transform_a, shielded_a, species_a);
+            let inverse_mass_b = 1.0 / mass(&transform_b, shielded_b, species_b);
+            let inverse_mass_sum = inverse_mass_a + inverse_mass_b;
+
+            // The lighter fish gets pushed further out of the way.
+            let correction = gap - 2.0 * COLLISION_MARGIN;
             let collision_normal = vector_between.normalize_or_zero();
 
-            transform_a.translation += collision_normal * correction;
-            transform_b.translation -= collision_normal * correction;
+            transform_a.translation +=
+                collision_normal * correction * inverse_mass_a / inverse_mass_sum;
+            transform_b.translation -=
+                collision_normal * correction * inverse_mass_b / inverse_mass_sum;
+
+            // Only fish still moving towards each other need an impulse to bounce them apart.
+            let normal = collision_normal.truncate();
+            let closing_speed = (velocity_b.0 - velocity_a.0).dot(normal);
+
+            if closing_speed < 0.0 {
+                let impulse =
+                    -(1.0 + ruleset.collision_restitution) * closing_speed / inverse_mass_sum;
+
+                velocity_a.0 -= normal * impulse * inverse_mass_a;
+                velocity_b.0 += normal * impulse * inverse_mass_b;
+            }
         }
 
         // Collide players against walls
-        for (_, mut velocity, mut transform, collision) in players.iter_mut() {
-            if transform.translation.x + collision.radius > LOGICAL_WIDTH as f32 {
-                transform.translation.x =
-                    LOGICAL_WIDTH as f32 - collision.radius - COLLISION_MARGIN;
+        for (_, _, mut velocity, mut transform, collision, _, _) in players.iter_mut() {
+            if transform.translation.x + collision.radius > bounds.max.x {
+                transform.translation.x = bounds.max.x - collision.radius - COLLISION_MARGIN;
                 velocity.0 = Vec2::new(0.0, Vec2::new(0.0, 1.0).dot(velocity.0));
                 found_collision = true;
             }
 
-            if transform.translation.y + collision.radius > LOGICAL_HEIGHT as f32 {
-                transform.translation.y =
-                    LOGICAL_HEIGHT as f32 - collision.radius - COLLISION_MARGIN;
+            if transform.translation.y + collision.radius > bounds.max.y {
+                transform.translation.y = bounds.max.y - collision.radius - COLLISION_MARGIN;
                 velocity.0 = Vec2::new(Vec2::new(1.0, 0.0).dot(velocity.0), 0.0);
                 found_collision = true;
             }
 
-            if transform.translation.x - collision.radius < 0.0 {
-                transform.translation.x = collision.radius + COLLISION_MARGIN;
+            if transform.translation.x - collision.radius < bounds.min.x {
+                transform.translation.x = bounds.min.x + collision.radius + COLLISION_MARGIN;
                 velocity.0 = Vec2::new(0.0, Vec2::new(0.0, 1.0).dot(velocity.0));
                 found_collision = true;
             }
 
-            if transform.translation.y - collision.radius < 0.0 {
-                transform.translation.y = collision.radius - COLLISION_MARGIN;
+            if transform.translation.y - collision.radius < bounds.min.y {
+                transform.translation.y = bounds.min.y + collision.radius + COLLISION_MARGIN;
                 velocity.0 = Vec2::new(Vec2::new(1.0, 0.0).dot(velocity.0), 0.0);
                 found_collision = true;
             }
@@ -0,0 +1,213 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use crate::core_components::Dead;
+
+use super::Player;
+
+pub(super) const SPEED_BOOST_MULTIPLIER: f32 = 1.5;
+pub(super) const RAPID_FIRE_INTERVAL_SECS: f32 = 0.15;
+pub(super) const RAPID_FIRE_COST_MULTIPLIER: f32 = 0.5;
+pub(super) const DOUBLE_DAMAGE_MULTIPLIER: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerUp {
+    SpeedBoost,
+    RapidFire,
+    FreeShield,
+    DoubleDamage,
+    Magnet,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 5] = [
+        PowerUp::SpeedBoost,
+        PowerUp::RapidFire,
+        PowerUp::FreeShield,
+        PowerUp::DoubleDamage,
+        PowerUp::Magnet,
+    ];
+
+    pub fn color(self) -> Color {
+        match self {
+            PowerUp::SpeedBoost => Color::rgb(0.5, 1.0, 1.0),
+            PowerUp::RapidFire => Color::rgb(1.0, 0.6, 0.2),
+            PowerUp::FreeShield => Color::rgb(0.6, 0.6, 1.0),
+            PowerUp::DoubleDamage => Color::rgb(1.0, 0.3, 0.3),
+            PowerUp::Magnet => Color::rgb(1.0, 1.0, 0.4),
+        }
+    }
+
+    fn duration_secs(self) -> f32 {
+        match self {
+            PowerUp::SpeedBoost => 8.0,
+            PowerUp::RapidFire => 6.0,
+            PowerUp::FreeShield => 5.0,
+            PowerUp::DoubleDamage => 8.0,
+            PowerUp::Magnet => 10.0,
+        }
+    }
+
+    /// Starts the power-up's effect on a player, restarting it if it's already active.
+    pub fn apply(self, player: &mut EntityCommands) {
+        let timer = Timer::from_seconds(self.duration_secs(), false);
+
+        match self {
+            PowerUp::SpeedBoost => player.insert(SpeedBoost(timer)),
+            PowerUp::RapidFire => player.insert(RapidFire {
+                timer,
+                cooldown: 0.0,
+            }),
+            PowerUp::FreeShield => player.insert(FreeShield(timer)),
+            PowerUp::DoubleDamage => player.insert(DoubleDamage(timer)),
+            PowerUp::Magnet => player.insert(Magnet(timer)),
+        };
+    }
+}
+
+pub(super) trait PowerUpEffect: Component {
+    fn timer(&mut self) -> &mut Timer;
+}
+
+#[derive(Component)]
+pub(super) struct SpeedBoost(Timer);
+
+#[derive(Component)]
+pub(super) struct RapidFire {
+    timer: Timer,
+    /// Seconds until the next shot can be fired.
+    pub(super) cooldown: f32,
+}
+
+#[derive(Component)]
+pub(super) struct FreeShield(Timer);
+
+#[derive(Component)]
+pub(super) struct DoubleDamage(Timer);
+
+#[derive(Component)]
+pub struct Magnet(Timer);
+
+impl PowerUpEffect for SpeedBoost {
+    fn timer(&mut self) -> &mut Timer {
+        &mut self.0
+    }
+}
+
+impl PowerUpEffect for RapidFire {
+    fn timer(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+}
+
+impl PowerUpEffect for FreeShield {
+    fn timer(&mut self) -> &mut Timer {
+        &mut self.0
+    }
+}
+
+impl PowerUpEffect for DoubleDamage {
+    fn timer(&mut self) -> &mut Timer {
+        &mut self.0
+    }
+}
+
+impl PowerUpEffect for Magnet {
+    fn timer(&mut self) -> &mut Timer {
+        &mut self.0
+    }
+}
+
+pub(super) fn expire_power_up<T: PowerUpEffect>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &mut T), With<Player>>,
+) {
+    for (player, mut effect) in players.iter_mut() {
+        if effect.timer().tick(time.delta()).finished() {
+            commands.entity(player).remove::<T>();
+        }
+    }
+}
+
+#[derive(Component)]
+pub(super) struct Aura(PowerUp);
+
+pub(super) fn update_auras(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    players: Query<
+        (
+            Entity,
+            Option<&Children>,
+            Option<&SpeedBoost>,
+            Option<&RapidFire>,
+            Option<&FreeShield>,
+            Option<&DoubleDamage>,
+            Option<&Magnet>,
+            Option<&Dead>,
+        ),
+        With<Player>,
+    >,
+    mut auras: Query<(&Aura, &mut Transform)>,
+) {
+    const AURA_SCALE: f32 = 2.2;
+    const AURA_SCALE_STEP: f32 = 0.25;
+    const AURA_PULSE_SPEED: f32 = 4.0;
+    const AURA_PULSE_AMOUNT: f32 = 0.05;
+
+    let pulse =
+        1.0 + (time.seconds_since_startup() as f32 * AURA_PULSE_SPEED).sin() * AURA_PULSE_AMOUNT;
+
+    for (player, children, speed_boost, rapid_fire, free_shield, double_damage, magnet, dead) in
+        players.iter()
+    {
+        // Dead fish lose their auras, whatever they were holding when they died.
+        let alive = dead.is_none();
+        let active = [
+            speed_boost.is_some(),
+            rapid_fire.is_some(),
+            free_shield.is_some(),
+            double_damage.is_some(),
+            magnet.is_some(),
+        ]
+        .map(|a| a && alive);
+
+        for (i, (power_up, is_active)) in PowerUp::ALL.into_iter().zip(active).enumerate() {
+            let aura = children.and_then(|children| {
+                children
+                    .iter()
+                    .copied()
+                    .find(|c| matches!(auras.get(*c), Ok((a, _)) if a.0 == power_up))
+            });
+
+            let scale = AURA_SCALE + AURA_SCALE_STEP * i as f32;
+
+            match (aura, is_active) {
+                (Some(aura), true) => {
+                    let (_, mut transform) = auras.get_mut(aura).expect("cannot find aura");
+                    transform.scale = Vec3::splat(scale * pulse);
+                }
+                (Some(aura), false) => commands.entity(aura).despawn_recursive(),
+                (None, true) => {
+                    let mut color = power_up.color();
+                    color.set_a(0.35);
+
+                    commands.entity(player).with_children(|player| {
+                        player
+                            .spawn_bundle(SpriteBundle {
+                                texture: asset_server.load("images/orb.png"),
+                                transform: Transform::from_scale(Vec3::splat(scale))
+                                    .with_translation(Vec3::new(0.0, 0.0, -1.0)),
+                                sprite: Sprite { color, ..default() },
+                                ..default()
+                            })
+                            .insert(Aura(power_up));
+                    });
+                }
+                (None, false) => (),
+            }
+        }
+    }
+}
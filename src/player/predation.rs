@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::background::spawn_bubble_group;
+use crate::configuration::Ruleset;
+use crate::core_components::{Dead, Energy, HitPoints, KilledBy, Shielded};
+use crate::energy_orbs::MAX_ENERGY;
+
+use super::reactions::{HitFlash, HIT_FLASH_SECS};
+use super::status_effects::{StatusEffect, StatusEffects};
+use super::{Player, Team};
+
+/// A fish swimming mouth first into a much smaller one.
+pub(super) struct Bite {
+    pub(super) predator: Entity,
+    pub(super) prey: Entity,
+}
+
+/// Still chewing.  The fish can't bite again until the timer runs out.
+#[derive(Component)]
+pub(super) struct BiteCooldown(Timer);
+
+pub(super) fn handle_bites(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    mut bites: EventReader<Bite>,
+    mut cooldowns: Query<(Entity, &mut BiteCooldown)>,
+    mut players: Query<
+        (
+            &Transform,
+            &Team,
+            &mut HitPoints,
+            &mut Energy,
+            &StatusEffects,
+            Option<&Shielded>,
+            Option<&Dead>,
+        ),
+        With<Player>,
+    >,
+) {
+    for (player, mut cooldown) in cooldowns.iter_mut() {
+        if cooldown.0.tick(time.delta()).finished() {
+            commands.entity(player).remove::<BiteCooldown>();
+        }
+    }
+
+    let predation = match &ruleset.predation {
+        Some(predation) => predation,
+        None => return,
+    };
+
+    // The cooldown only lands once commands are applied, so keep track of this frame's biters
+    // until then.
+    let mut biters = HashSet::new();
+
+    for Bite { predator, prey } in bites.iter() {
+        if cooldowns.get(*predator).is_ok() || biters.contains(predator) {
+            continue;
+        }
+
+        let predator_team = match players.get(*predator) {
+            Ok((_, team, _, _, _, _, None)) => *team,
+            _ => continue,
+        };
+
+        let (transform, team, mut hp, mut energy, status_effects, shielded, dead) =
+            match players.get_mut(*prey) {
+                Ok(prey) => prey,
+                Err(_) => continue,
+            };
+
+        if dead.is_some()
+            || shielded.is_some()
+            || status_effects.has(StatusEffect::Invulnerability)
+            || (!ruleset.friendly_fire && *team == predator_team)
+        {
+            continue;
+        }
+
+        hp.0 = hp.0.saturating_sub(predation.bite_damage);
+        debug!("{:?} hp: {}", prey, hp.0);
+
+        let stolen = energy.0.min(predation.energy_stolen).max(0.0);
+        energy.0 -= stolen;
+        debug!("Player {:?} energy: {}", prey, energy.0);
+
+        spawn_bubble_group(
+            &mut commands,
+            &asset_server,
+            transform.translation,
+            5,
+            -25.0..25.0,
+            -25.0..25.0,
+            0.0..0.0001,
+        );
+
+        commands
+            .entity(*prey)
+            .insert(HitFlash(Timer::from_seconds(HIT_FLASH_SECS, false)));
+
+        if hp.0 == 0 {
+            commands
+                .entity(*prey)
+                .insert(Dead)
+                .insert(KilledBy(*predator));
+        }
+
+        if let Ok((_, _, _, mut energy, ..)) = players.get_mut(*predator) {
+            energy.0 += stolen.min(MAX_ENERGY - energy.0).max(0.0);
+            debug!("Player {:?} energy: {}", predator, energy.0);
+        }
+
+        biters.insert(*predator);
+        commands
+            .entity(*predator)
+            .insert(BiteCooldown(Timer::from_seconds(
+                predation.bite_cooldown_secs,
+                false,
+            )));
+    }
+}
@@ -1,58 +1,340 @@
+use std::collections::HashSet;
+
 use bevy::ecs::query::WorldQuery;
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 
 use crate::background::spawn_bubble_group;
+use crate::collision::{separation, ArenaBounds, CollisionLayer, CollisionLayers, Contacts};
+use crate::configuration::{Ruleset, ShieldModel};
 use crate::core_components::{
-    CollisionCircle, Dead, Energy, HitPoints, Originator, Projectile, Shielded, Velocity,
+    AngularVelocity, CollisionCircle, Damage, Dead, Energy, HitPoints, KilledBy, Originator,
+    Projectile, Shielded, Velocity,
 };
+use crate::currents::current_at;
+use crate::ink::{hidden_in_ink, InkCloud};
 
 use super::input::Action;
-use super::model::BodyPart;
-use super::Player;
+use super::model::{locate_hit, relative_size, BodyPart, BodyPartQuery};
+use super::power_ups::{
+    DoubleDamage, FreeShield, RapidFire, DOUBLE_DAMAGE_MULTIPLIER, RAPID_FIRE_COST_MULTIPLIER,
+    RAPID_FIRE_INTERVAL_SECS,
+};
+use super::reactions::{
+    HitFlash, InjuredFin, InjuredTail, HIT_FLASH_SECS, HIT_STUN_SECS, INJURY_SECS,
+};
+use super::shield::{ParryWindow, ShieldCracks, PARRY_ENERGY_COST};
+use super::status_effects::{StatusEffect, StatusEffects};
+use super::weapons::{
+    ChargingShot, Homing, Weapon, WeaponStats, CHARGE_MAX_MULTIPLIER, CHARGE_SECS,
+};
+use super::{Player, Team};
+
+/// How quickly a projectile's drift catches up with the current it's in.
+const CURRENT_PULL: f32 = 0.05;
+/// Seconds before a projectile stuck in a fish falls out by itself.
+const STUCK_SECS: f32 = 8.0;
+/// Extra damage for hitting a fish in the head.
+const HEADSHOT_BONUS_DAMAGE: u32 = 1;
+/// How much of a projectile's velocity is passed on to whatever it hits.
+const KNOCKBACK: f32 = 0.4;
+/// How much an off-centre hit spins its target, per unit of offset and speed.
+const KNOCKBACK_SPIN: f32 = 0.0001;
+
+#[derive(Component)]
+pub(super) struct Lifetime(Timer);
+
+#[derive(Component)]
+pub(super) struct Ricochets(u32);
+
+/// How fast the water is carrying a projectile along, on top of its own velocity.
+#[derive(Component, Default)]
+pub(super) struct Drift(Vec2);
+
+/// A projectile lodged in a fish, weighing it down until it falls out.
+#[derive(Component)]
+pub(super) struct Stuck {
+    pub(super) fish: Entity,
+    timer: Timer,
+}
 
 pub(super) fn handle_shooting(
     mut commands: Commands,
+    time: Res<Time>,
     actions: Res<Input<Action>>,
     asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
     mut players: Query<
         (
             Entity,
             &mut Energy,
+            &Weapon,
+            &Team,
             &Velocity,
             &Transform,
+            Option<&mut ChargingShot>,
+            Option<&mut RapidFire>,
+            Option<&DoubleDamage>,
             Option<&Shielded>,
+            &StatusEffects,
         ),
         With<Player>,
     >,
 ) {
-    const PROJECTILE_ENERGY_COST: f32 = 1.0;
-    const PROJECTILE_SPEED: f32 = 12.0;
+    for (
+        player,
+        mut energy,
+        weapon,
+        team,
+        velocity,
+        transform,
+        charging,
+        rapid_fire,
+        double_damage,
+        shielded,
+        status_effects,
+    ) in players.iter_mut()
+    {
+        if !status_effects.can_act() {
+            continue;
+        }
+
+        let mut stats = weapon.stats();
+
+        if double_damage.is_some() {
+            stats.damage *= DOUBLE_DAMAGE_MULTIPLIER;
+        }
+
+        // Big fish hit harder.  Even the smallest still does some damage.
+        let size_damage = stats.damage as f32 * ruleset.size_stats.damage(relative_size(transform));
+        stats.damage = (size_damage.round() as u32).max(1);
+
+        if let Some(mut charging) = charging {
+            charging.0.tick(time.delta());
+
+            if shielded.is_some() || *weapon != Weapon::Charged {
+                commands.entity(player).remove::<ChargingShot>();
+            } else if !actions.pressed(Action::Shoot(player)) {
+                commands.entity(player).remove::<ChargingShot>();
+
+                // Energy spent on something else while charging can leave too little for a shot,
+                // in which case the charge fizzles out in a puff of bubbles.
+                if energy.0 < stats.energy_cost {
+                    spawn_bubble_group(
+                        &mut commands,
+                        &asset_server,
+                        transform.translation,
+                        3,
+                        -10.0..10.0,
+                        -10.0..10.0,
+                        0.0..0.0001,
+                    );
+                    continue;
+                }
+
+                let power = (1.0 + (CHARGE_MAX_MULTIPLIER - 1.0) * charging.0.percent())
+                    .min(energy.0 / stats.energy_cost);
+
+                energy.0 -= stats.energy_cost * power;
+                debug!("Player {:?} energy: {}", player, energy.0);
+
+                spawn_projectiles(
+                    &mut commands,
+                    &asset_server,
+                    &ruleset,
+                    player,
+                    *team,
+                    transform,
+                    velocity,
+                    &stats,
+                    power,
+                );
+            }
+
+            continue;
+        }
+
+        // Rapid fire keeps shooting while the key is held, which a charged weapon needs for
+        // charging instead.
+        if let Some(mut rapid_fire) = rapid_fire.filter(|_| *weapon != Weapon::Charged) {
+            rapid_fire.cooldown -= time.delta_seconds();
+            stats.energy_cost *= RAPID_FIRE_COST_MULTIPLIER;
+
+            if actions.pressed(Action::Shoot(player))
+                && rapid_fire.cooldown <= 0.0
+                && energy.0 >= stats.energy_cost
+                && shielded.is_none()
+            {
+                rapid_fire.cooldown = RAPID_FIRE_INTERVAL_SECS;
+
+                energy.0 -= stats.energy_cost;
+                debug!("Player {:?} energy: {}", player, energy.0);
+
+                spawn_projectiles(
+                    &mut commands,
+                    &asset_server,
+                    &ruleset,
+                    player,
+                    *team,
+                    transform,
+                    velocity,
+                    &stats,
+                    1.0,
+                );
+            }
+
+            continue;
+        }
 
-    for (player, mut energy, velocity, transform, shielded) in players.iter_mut() {
         if actions.just_pressed(Action::Shoot(player))
-            && energy.0 >= PROJECTILE_ENERGY_COST
+            && energy.0 >= stats.energy_cost
             && shielded.is_none()
         {
-            energy.0 -= PROJECTILE_ENERGY_COST;
-            println!("Player {:?} energy: {}", player, energy.0);
-
-            commands
-                .spawn()
-                .insert(Projectile)
-                .insert(Originator(player))
-                .insert(Velocity(
-                    ((transform.rotation * Vec3::new(0.0, PROJECTILE_SPEED, 0.0)).truncate()
-                        + velocity.0 / 2.0)
-                        .normalize()
-                        * PROJECTILE_SPEED,
-                ))
-                .insert_bundle(SpriteBundle {
-                    texture: asset_server.load("images/projectile.png"),
-                    transform: (*transform).with_scale(Vec3::splat(0.2))
-                        * Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
-                    ..default()
-                })
-                .insert(CollisionCircle { radius: 64.0 * 0.2 });
+            if *weapon == Weapon::Charged {
+                commands
+                    .entity(player)
+                    .insert(ChargingShot(Timer::from_seconds(CHARGE_SECS, false)));
+                continue;
+            }
+
+            energy.0 -= stats.energy_cost;
+            debug!("Player {:?} energy: {}", player, energy.0);
+
+            spawn_projectiles(
+                &mut commands,
+                &asset_server,
+                &ruleset,
+                player,
+                *team,
+                transform,
+                velocity,
+                &stats,
+                1.0,
+            );
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_projectiles(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    ruleset: &Ruleset,
+    player: Entity,
+    team: Team,
+    transform: &Transform,
+    velocity: &Velocity,
+    stats: &WeaponStats,
+    power: f32,
+) {
+    let scale = stats.scale * power.sqrt();
+
+    for angle in stats.angles.iter().copied() {
+        let rotation = transform.rotation * Quat::from_rotation_z(angle);
+
+        let mut projectile = spawn_projectile(
+            commands,
+            asset_server,
+            ruleset,
+            player,
+            transform
+                .with_rotation(rotation)
+                .with_scale(Vec3::splat(scale))
+                * Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
+            ((rotation * Vec3::new(0.0, stats.speed, 0.0)).truncate() + velocity.0 / 2.0)
+                .normalize()
+                * stats.speed,
+            (stats.damage as f32 * power).round() as u32,
+            stats.color,
+            stats.texture,
+        );
+
+        // Fish fighting the big fish together can't hurt each other.
+        if ruleset.boss_fight.is_some() {
+            projectile.insert(
+                CollisionLayers::new(CollisionLayer::Projectile)
+                    .with_filter(CollisionLayer::BigFish),
+            );
+        }
+
+        if let Some(turn_rate) = stats.homing {
+            projectile.insert(Homing { turn_rate, team });
+        }
+    }
+}
+
+/// Spawns a single projectile that can hit anything with hit points except its originator.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_projectile<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    asset_server: &Res<AssetServer>,
+    ruleset: &Ruleset,
+    originator: Entity,
+    transform: Transform,
+    velocity: Vec2,
+    damage: u32,
+    color: Color,
+    texture: &'static str,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut projectile = commands.spawn();
+
+    projectile
+        .insert(Projectile)
+        .insert(Originator(originator))
+        .insert(Damage(damage))
+        .insert(Velocity(velocity))
+        .insert_bundle(SpriteBundle {
+            texture: asset_server.load(texture),
+            transform,
+            sprite: Sprite { color, ..default() },
+            ..default()
+        })
+        .insert(CollisionCircle {
+            radius: 64.0 * transform.scale.x,
+        })
+        .insert(
+            CollisionLayers::new(CollisionLayer::Projectile)
+                .with_filter(CollisionLayer::Player)
+                .with_filter(CollisionLayer::Shield)
+                .with_filter(CollisionLayer::BigFish),
+        )
+        .insert(Lifetime(Timer::from_seconds(
+            ruleset.projectile_lifetime,
+            false,
+        )))
+        .insert(Ricochets(ruleset.projectile_ricochets))
+        .insert(Drift::default());
+
+    projectile
+}
+
+pub(super) fn steer_homing_projectiles(
+    mut projectiles: Query<
+        (&mut Transform, &mut Velocity, &Originator, &Homing),
+        (With<Projectile>, Without<Player>),
+    >,
+    players: Query<(Entity, &Transform, &Team), (With<Player>, Without<Dead>)>,
+    clouds: Query<(&Transform, &InkCloud), Without<Projectile>>,
+) {
+    for (mut transform, mut velocity, originator, homing) in projectiles.iter_mut() {
+        let position = transform.translation.truncate();
+        let target = players
+            .iter()
+            .filter(|(p, _, t)| *p != originator.0 && **t != homing.team)
+            .map(|(_, t, _)| t.translation.truncate())
+            .filter(|p| !hidden_in_ink(*p, clouds.iter()))
+            .map(|p| p - position)
+            .min_by(|a, b| a.length().partial_cmp(&b.length()).unwrap());
+
+        if let Some(target) = target {
+            let turn = velocity
+                .0
+                .angle_between(target)
+                .max(-homing.turn_rate)
+                .min(homing.turn_rate);
+
+            velocity.0 = (Quat::from_rotation_z(turn) * velocity.0.extend(0.0)).truncate();
+            transform.rotation = Quat::from_rotation_z(turn) * transform.rotation;
         }
     }
 }
@@ -63,63 +345,311 @@ pub(super) struct HpEntityQuery<'w> {
     entity: Entity,
     hp: &'w mut HitPoints,
     transform: &'w Transform,
-    collision: &'w CollisionCircle,
     shielded: Option<&'w Shielded>,
     children: Option<&'w Children>,
+    player: Option<&'w Player>,
+    velocity: Option<&'w mut Velocity>,
+    angular_velocity: Option<&'w mut AngularVelocity>,
+    energy: Option<&'w mut Energy>,
+    parry_window: Option<&'w ParryWindow>,
+    shield_cracks: Option<&'w mut ShieldCracks>,
+    free_shield: Option<&'w FreeShield>,
+    status_effects: Option<&'w mut StatusEffects>,
 }
 
-pub(super) fn handle_projectiles(
+pub(super) fn move_projectiles(
+    ruleset: Res<Ruleset>,
+    mut projectiles: Query<(&mut Transform, &Velocity, &mut Drift), With<Projectile>>,
+) {
+    for (mut transform, velocity, mut drift) in projectiles.iter_mut() {
+        // Like a fish, a projectile is only ever carried as fast as the water is flowing.
+        let current = current_at(&ruleset, transform.translation.truncate());
+        let d = drift.0;
+        drift.0 = d + (current - d) * CURRENT_PULL;
+
+        let motion = velocity.0 + drift.0;
+        transform.translation += motion.extend(0.0);
+
+        if let Some(direction) = motion.try_normalize() {
+            transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_between(direction));
+        }
+    }
+}
+
+pub(super) fn cull_projectiles(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    bounds: Res<ArenaBounds>,
     mut projectiles: Query<
         (
             Entity,
             &mut Transform,
-            &Velocity,
-            &Originator,
+            &mut Velocity,
+            &mut Lifetime,
+            &mut Ricochets,
             &CollisionCircle,
         ),
         With<Projectile>,
     >,
-    mut hp_entities: Query<HpEntityQuery, Without<Projectile>>,
-    body_parts: Query<(Entity, &GlobalTransform), With<BodyPart>>,
 ) {
-    for (_, mut transform, velocity, _, _) in projectiles.iter_mut() {
-        transform.translation += velocity.0.extend(0.0);
+    for (projectile, mut transform, mut velocity, mut lifetime, mut ricochets, collision) in
+        projectiles.iter_mut()
+    {
+        lifetime.0.tick(time.delta());
+
+        if lifetime.0.finished() {
+            commands.entity(projectile).despawn();
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let min = bounds.min + Vec2::splat(collision.radius);
+        let max = bounds.max - Vec2::splat(collision.radius);
+
+        if ricochets.0 > 0 {
+            let mut bounced = false;
+
+            if (position.x < min.x && velocity.0.x < 0.0)
+                || (position.x > max.x && velocity.0.x > 0.0)
+            {
+                velocity.0.x = -velocity.0.x;
+                bounced = true;
+            }
+
+            if (position.y < min.y && velocity.0.y < 0.0)
+                || (position.y > max.y && velocity.0.y > 0.0)
+            {
+                velocity.0.y = -velocity.0.y;
+                bounced = true;
+            }
+
+            if bounced {
+                ricochets.0 -= 1;
+                transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_between(velocity.0));
+            }
+        } else if position.x < bounds.min.x - collision.radius
+            || position.y < bounds.min.y - collision.radius
+            || position.x > bounds.max.x + collision.radius
+            || position.y > bounds.max.y + collision.radius
+        {
+            commands.entity(projectile).despawn();
+        }
     }
+}
+
+pub(super) fn handle_projectiles(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ruleset: Res<Ruleset>,
+    contacts: Res<Contacts>,
+    mut projectiles: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Originator,
+            &Damage,
+            &mut Lifetime,
+        ),
+        With<Projectile>,
+    >,
+    mut hp_entities: Query<HpEntityQuery, Without<Projectile>>,
+    projectile_entities: Query<Entity, With<Projectile>>,
+    body_parts: BodyPartQuery,
+    teams: Query<&Team>,
+    circles: Query<&CollisionCircle>,
+) {
+    // A projectile can touch several things in the same frame, but it only hits the closest.
+    let mut spent = HashSet::new();
+
+    // Projectiles can sail through the gaps between a fish's fins and tail, so keep checking
+    // them for as long as they're touching something, not just when they first touch it.
+    let mut touching = projectile_entities
+        .iter()
+        .flat_map(|p| contacts.colliding_with(p).map(move |t| (p, t)))
+        .map(|(p, t)| {
+            let gap = match (
+                projectiles.get(p),
+                hp_entities.get(t),
+                circles.get(p),
+                circles.get(t),
+            ) {
+                (Ok((transform, ..)), Ok(e), Ok(circle_p), Ok(circle_t)) => separation(
+                    transform.translation.truncate(),
+                    circle_p,
+                    e.transform.translation.truncate(),
+                    circle_t,
+                ),
+                _ => f32::INFINITY,
+            };
+
+            (p, t, gap)
+        })
+        .collect::<Vec<_>>();
+
+    touching.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+
+    for (projectile, target, _) in touching {
+        if spent.contains(&projectile) {
+            continue;
+        }
+
+        let (mut transform, mut projectile_velocity, mut originator, damage, mut lifetime) =
+            projectiles
+                .get_mut(projectile)
+                .expect("cannot find projectile");
+
+        // Expired projectiles are already on their way out.
+        if target == originator.0 || lifetime.0.finished() {
+            continue;
+        }
+
+        // Without friendly fire, projectiles pass straight through teammates.
+        if !ruleset.friendly_fire
+            && matches!((teams.get(originator.0), teams.get(target)), (Ok(a), Ok(b)) if a == b)
+        {
+            continue;
+        }
+
+        let shooter_position = hp_entities
+            .get(originator.0)
+            .ok()
+            .map(|o| o.transform.translation.truncate());
+
+        if let Ok(mut e) = hp_entities.get_mut(target) {
+            if matches!(&e.status_effects, Some(s) if s.has(StatusEffect::Invulnerability)) {
+                continue;
+            }
+
+            // A shield covers the whole fish, but otherwise the projectile has to touch an
+            // actual part of it.
+            let hit = match (e.children, e.player, e.shielded) {
+                (Some(children), Some(_), None) => {
+                    let point = transform.translation.truncate();
+
+                    match locate_hit(e.transform, children, point, &body_parts) {
+                        Some(hit) => Some(hit),
+                        None => continue,
+                    }
+                }
+                _ => None,
+            };
+
+            spent.insert(projectile);
+
+            let can_parry = e.shielded.is_some()
+                && e.parry_window.is_some()
+                && matches!(&e.energy, Some(energy) if energy.0 >= PARRY_ENERGY_COST);
+
+            // A well-timed shield sends the projectile back where it came from, as the parrier's.
+            if can_parry {
+                let energy = e.energy.as_mut().unwrap();
+                energy.0 -= PARRY_ENERGY_COST;
+                debug!("Player {:?} energy: {}", e.entity, energy.0);
+
+                let position = transform.translation.truncate();
+                let direction = shooter_position
+                    .and_then(|s| (s - position).try_normalize())
+                    .unwrap_or(-projectile_velocity.0.normalize_or_zero());
+
+                projectile_velocity.0 = direction * projectile_velocity.0.length();
+                transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_between(direction));
+                originator.0 = e.entity;
+                lifetime.0.reset();
+
+                spawn_bubble_group(
+                    &mut commands,
+                    &asset_server,
+                    transform.translation,
+                    3,
+                    -10.0..10.0,
+                    -10.0..10.0,
+                    0.0..0.001,
+                );
+
+                continue;
+            }
+
+            // Under the durability model, every block costs energy and wears the shield down.
+            if let (ShieldModel::Durability { block_cost, .. }, Some(cracks)) =
+                (ruleset.shield_model, &mut e.shield_cracks)
+            {
+                cracks.0 += 1;
+
+                if e.free_shield.is_none() {
+                    if let Some(energy) = &mut e.energy {
+                        energy.0 = (energy.0 - block_cost).max(0.0);
+                        debug!("Player {:?} energy: {}", e.entity, energy.0);
+                    }
+                }
+            }
 
-    for (projectile, mut transform, _, originator, collision) in projectiles.iter_mut() {
-        let hit = hp_entities
-            .iter_mut()
-            .filter(|e| e.entity != originator.0)
-            .map(|e| {
-                let distance = (transform.translation - e.transform.translation)
-                    .truncate()
-                    .length()
-                    - e.collision.radius
-                    - collision.radius;
-
-                (e, distance)
-            })
-            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-            .filter(|a| a.1 <= 0.0)
-            .map(|(e, _)| e);
-
-        if let Some(mut e) = hit {
             if e.shielded.is_none() {
-                commands.entity(projectile).remove::<Projectile>();
-
-                let (parent_entity, parent_transform) = if let Some(children) = e.children {
-                    children
-                        .iter()
-                        .filter_map(|c| body_parts.get(*c).ok())
-                        .map(|(c, t)| (c, t, (t.translation - transform.translation).length()))
-                        .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
-                        .map(|(c, t, _)| (c, (*t).into()))
-                        .unwrap_or((e.entity, e.transform.clone()))
-                } else {
-                    (e.entity, e.transform.clone())
-                };
+                let offset = (transform.translation - e.transform.translation).truncate();
+
+                if let Some(velocity) = &mut e.velocity {
+                    velocity.0 += projectile_velocity.0 * KNOCKBACK;
+                }
+
+                // Off-centre hits spin the fish around.
+                if let Some(angular_velocity) = &mut e.angular_velocity {
+                    angular_velocity.0 += offset.perp_dot(projectile_velocity.0) * KNOCKBACK_SPIN;
+                }
+
+                if let Some(status_effects) = &mut e.status_effects {
+                    status_effects.apply(StatusEffect::Stun, HIT_STUN_SECS);
+                }
+
+                if e.player.is_some() {
+                    commands
+                        .entity(e.entity)
+                        .insert(HitFlash(Timer::from_seconds(HIT_FLASH_SECS, false)));
+                }
+            }
+
+            let mut damage = damage.0;
+
+            match hit.map(|(_, body_part, _)| body_part) {
+                Some(BodyPart::Head) => damage += HEADSHOT_BONUS_DAMAGE,
+                Some(BodyPart::Tail) => {
+                    commands
+                        .entity(e.entity)
+                        .insert(InjuredTail(Timer::from_seconds(INJURY_SECS, false)));
+                }
+                Some(BodyPart::LeftFin | BodyPart::RightFin) => {
+                    commands
+                        .entity(e.entity)
+                        .insert(InjuredFin(Timer::from_seconds(INJURY_SECS, false)));
+                }
+                _ => {}
+            }
+
+            if e.shielded.is_none() && e.hp.0 > 0 {
+                e.hp.0 = e.hp.0.saturating_sub(damage);
+                debug!("{:?} hp: {}", e.entity, e.hp.0);
+                if e.hp.0 == 0 {
+                    commands
+                        .entity(e.entity)
+                        .insert(Dead)
+                        .insert(KilledBy(originator.0));
+                }
+            }
+
+            // Only fish keep what hits them.  Everything else bursts it, as does a shield.
+            if e.shielded.is_none() && e.player.is_some() {
+                commands
+                    .entity(projectile)
+                    .remove::<Projectile>()
+                    .remove::<CollisionLayers>()
+                    .remove::<Lifetime>()
+                    .remove::<Ricochets>()
+                    .insert(Stuck {
+                        fish: e.entity,
+                        timer: Timer::from_seconds(STUCK_SECS, false),
+                    });
+
+                let (parent_entity, parent_transform) = hit
+                    .map(|(part, _, part_transform)| (part, part_transform))
+                    .unwrap_or((e.entity, *e.transform));
 
                 let mut vector = transform.translation - parent_transform.translation;
                 vector = parent_transform.rotation.inverse() * vector;
@@ -130,14 +660,6 @@ pub(super) fn handle_projectiles(
                     .with_scale(transform.scale / parent_transform.scale);
 
                 commands.entity(parent_entity).push_children(&[projectile]);
-
-                if e.hp.0 > 0 {
-                    e.hp.0 -= 1;
-                    println!("Player {:?} hp: {}", e.entity, e.hp.0);
-                    if e.hp.0 == 0 {
-                        commands.entity(e.entity).insert(Dead);
-                    }
-                }
             } else {
                 commands.entity(projectile).despawn();
                 spawn_bubble_group(
@@ -153,3 +675,33 @@ pub(super) fn handle_projectiles(
         }
     }
 }
+
+/// Lets go of stuck projectiles once they've been in long enough, or as soon as the fish they're
+/// stuck in raises its shield.
+pub(super) fn shake_off_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut stuck: Query<(Entity, &mut Stuck, &GlobalTransform)>,
+    fish: Query<Option<&Shielded>>,
+) {
+    for (projectile, mut stuck, transform) in stuck.iter_mut() {
+        // If the fish has gone without taking it along, don't leave it hanging about.
+        let shaken_off = fish
+            .get(stuck.fish)
+            .map_or(true, |shielded| shielded.is_some());
+
+        if stuck.timer.tick(time.delta()).finished() || shaken_off {
+            commands.entity(projectile).despawn_recursive();
+            spawn_bubble_group(
+                &mut commands,
+                &asset_server,
+                transform.translation,
+                3,
+                -10.0..10.0,
+                -10.0..10.0,
+                0.0..0.001,
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+
+use crate::core_components::Originator;
+
+use super::model::BodyPart;
+use super::status_effects::{status_tint, StatusEffects};
+use super::{Player, PlayerColor};
+
+pub(super) const HIT_STUN_SECS: f32 = 0.25;
+pub(super) const HIT_FLASH_SECS: f32 = 0.15;
+pub(super) const INJURY_SECS: f32 = 3.0;
+/// How much of its usual turning a fish with an injured tail manages.
+pub(super) const INJURED_TAIL_TURNING: f32 = 0.5;
+/// How much of its usual acceleration a fish with an injured fin manages.
+pub(super) const INJURED_FIN_ACCELERATION: f32 = 0.5;
+
+/// Briefly blanches a fish that's just been hit.
+#[derive(Component)]
+pub(super) struct HitFlash(pub(super) Timer);
+
+/// Hit in the tail.  The fish turns sluggishly until the timer runs out.
+#[derive(Component)]
+pub(super) struct InjuredTail(pub(super) Timer);
+
+/// Hit in a fin.  The fish speeds up sluggishly until the timer runs out.
+#[derive(Component)]
+pub(super) struct InjuredFin(pub(super) Timer);
+
+pub(super) fn heal_injuries(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut tails: Query<(Entity, &mut InjuredTail), With<Player>>,
+    mut fins: Query<(Entity, &mut InjuredFin), With<Player>>,
+) {
+    for (player, mut injury) in tails.iter_mut() {
+        if injury.0.tick(time.delta()).finished() {
+            commands.entity(player).remove::<InjuredTail>();
+        }
+    }
+
+    for (player, mut injury) in fins.iter_mut() {
+        if injury.0.tick(time.delta()).finished() {
+            commands.entity(player).remove::<InjuredFin>();
+        }
+    }
+}
+
+/// Colours each fish's body by whatever it's suffering from, and flashes it if it's just been hit.
+pub(super) fn tint_body_parts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(
+        Entity,
+        &PlayerColor,
+        &Children,
+        &StatusEffects,
+        Option<&mut HitFlash>,
+    )>,
+    body_parts: Query<(), With<BodyPart>>,
+    mut sprites: Query<(&mut Sprite, Option<&Children>), (Without<Originator>, Without<Player>)>,
+) {
+    fn tint(
+        entity: Entity,
+        color: Color,
+        sprites: &mut Query<
+            (&mut Sprite, Option<&Children>),
+            (Without<Originator>, Without<Player>),
+        >,
+    ) {
+        if let Ok((mut sprite, children)) = sprites.get_mut(entity) {
+            sprite.color = color;
+
+            if let Some(children) = children.cloned() {
+                for child in children.iter() {
+                    tint(*child, color, sprites);
+                }
+            }
+        }
+    }
+
+    for (player, color, children, status_effects, flash) in players.iter_mut() {
+        let whiteness = match flash {
+            Some(mut flash) => {
+                if flash.0.tick(time.delta()).finished() {
+                    commands.entity(player).remove::<HitFlash>();
+                    0.0
+                } else {
+                    1.0 - flash.0.percent()
+                }
+            }
+            None => 0.0,
+        };
+
+        let color = status_tint(status_effects, color.0);
+        let flash_color = Color::rgba(
+            color.r() + (1.0 - color.r()) * whiteness,
+            color.g() + (1.0 - color.g()) * whiteness,
+            color.b() + (1.0 - color.b()) * whiteness,
+            color.a(),
+        );
+
+        // Only the fish itself flashes, not its auras or anything stuck in it.
+        for child in children.iter().filter(|c| body_parts.get(**c).is_ok()) {
+            tint(*child, flash_color, &mut sprites);
+        }
+    }
+}
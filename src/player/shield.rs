@@ -1,37 +1,89 @@
 use bevy::prelude::*;
 
 use crate::background::spawn_bubble_group;
+use crate::collision::{CollisionLayer, CollisionLayers};
+use crate::configuration::{Ruleset, ShieldModel};
 use crate::core_components::{Energy, Shield, Shielded};
 
 use super::input::Action;
+use super::power_ups::FreeShield;
+use super::status_effects::StatusEffects;
 use super::Player;
 
 pub const PLAYER_SHIELD_SCALE: f32 = 1.2;
 
+/// Seconds after raising a shield during which projectiles are sent back rather than blocked.
+const PARRY_WINDOW_SECS: f32 = 0.2;
+pub(super) const PARRY_ENERGY_COST: f32 = 1.0;
+
+/// A freshly raised shield, ready to parry.
+#[derive(Component)]
+pub(super) struct ParryWindow(Timer);
+
+/// How many projectiles a shield has blocked since it was raised, under the durability model.
+#[derive(Component)]
+pub(super) struct ShieldCracks(pub(super) u32);
+
+/// How worn a shield looks, from 0 for fresh to 1 for about to burst.
+fn shield_color(wear: f32) -> Color {
+    Color::rgba(1.0, 1.0 - 0.5 * wear, 1.0 - 0.6 * wear, 0.8 - 0.4 * wear)
+}
+
 pub(super) fn handle_shielding(
     mut commands: Commands,
     time: Res<Time>,
+    ruleset: Res<Ruleset>,
     actions: Res<Input<Action>>,
     asset_server: Res<AssetServer>,
     mut players: Query<
         (
             Entity,
             &mut Energy,
+            &mut CollisionLayers,
             &Transform,
             &Children,
+            Option<&FreeShield>,
             Option<&Shielded>,
+            Option<&mut ParryWindow>,
+            &StatusEffects,
+            Option<&ShieldCracks>,
         ),
         With<Player>,
     >,
-    mut shields: Query<(&mut Transform, &Parent), (With<Shield>, Without<Player>)>,
+    mut shields: Query<(&mut Transform, &mut Sprite, &Parent), (With<Shield>, Without<Player>)>,
 ) {
     const SHIELD_DRAIN_RATE: f32 = 2.0;
 
-    for (player, mut energy, transform, children, shielded) in players.iter_mut() {
-        if actions.just_pressed(Action::Shield(player)) && energy.0 > 0.0 {
+    for (
+        player,
+        mut energy,
+        mut layers,
+        transform,
+        children,
+        free_shield,
+        shielded,
+        parry_window,
+        status_effects,
+        cracks,
+    ) in players.iter_mut()
+    {
+        if let Some(mut parry_window) = parry_window {
+            if parry_window.0.tick(time.delta()).finished() || shielded.is_none() {
+                commands.entity(player).remove::<ParryWindow>();
+            }
+        }
+
+        if actions.just_pressed(Action::Shield(player))
+            && (energy.0 > 0.0 || free_shield.is_some())
+            && status_effects.can_act()
+        {
+            layers.insert(CollisionLayer::Shield);
+
             commands
                 .entity(player)
                 .insert(Shielded)
+                .insert(ParryWindow(Timer::from_seconds(PARRY_WINDOW_SECS, false)))
+                .insert(ShieldCracks(0))
                 .with_children(|player| {
                     player
                         .spawn_bundle(SpriteBundle {
@@ -39,25 +91,48 @@ pub(super) fn handle_shielding(
                             transform: Transform::from_scale(Vec3::splat(PLAYER_SHIELD_SCALE))
                                 .with_translation(Vec3::new(0.0, 0.0, 5.0)),
                             sprite: Sprite {
-                                color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+                                color: shield_color(0.0),
                                 ..default()
                             },
                             ..default()
                         })
                         .insert(Shield);
                 });
-        } else if shielded.is_some() && actions.pressed(Action::Shield(player)) {
+        } else if shielded.is_some()
+            && actions.pressed(Action::Shield(player))
+            && free_shield.is_none()
+            && ruleset.shield_model == ShieldModel::Drain
+        {
             energy.0 -= time.delta_seconds() * SHIELD_DRAIN_RATE;
             println!("Player {:?} energy: {}", player, energy.0);
         }
 
-        if shielded.is_some() && (actions.just_released(Action::Shield(player)) || energy.0 <= 0.0)
+        let wear = match (ruleset.shield_model, cracks) {
+            (ShieldModel::Durability { hits, .. }, Some(cracks)) => {
+                (cracks.0 as f32 / hits.max(1) as f32).min(1.0)
+            }
+            _ => 0.0,
+        };
+        let burst = wear >= 1.0;
+
+        if shielded.is_some()
+            && (actions.just_released(Action::Shield(player))
+                || (energy.0 <= 0.0 && free_shield.is_none())
+                || burst)
         {
             if energy.0 < 0.0 {
                 energy.0 = 0.0;
             }
 
-            commands.entity(player).remove::<Shielded>();
+            if burst {
+                debug!("Player {:?} shield burst", player);
+            }
+
+            commands
+                .entity(player)
+                .remove::<Shielded>()
+                .remove::<ShieldCracks>();
+            layers.remove(CollisionLayer::Shield);
 
             let shield = children
                 .iter()
@@ -73,15 +148,18 @@ pub(super) fn handle_shielding(
                 &mut commands,
                 &asset_server,
                 transform.translation,
-                5,
+                if burst { 12 } else { 5 },
                 range.clone(),
                 range,
                 5.0..5.0001,
             );
         }
 
-        if let Some((mut shield_transform, _)) = shields.iter_mut().find(|(_, p)| ***p == player) {
+        if let Some((mut shield_transform, mut sprite, _)) =
+            shields.iter_mut().find(|(_, _, p)| ***p == player)
+        {
             shield_transform.rotation = transform.rotation.inverse();
+            sprite.color = shield_color(wear);
         }
     }
 }
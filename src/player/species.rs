@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+use super::abilities::INFLATE_SCALE;
+use super::model::ModelDefinition;
+
+/// What kind of fish a player is.  Each one swims differently and has its own trick.
+#[derive(Clone, Component, Copy, Debug, PartialEq)]
+pub enum Species {
+    Classic,
+    /// Slow and tough.  Puffs up into a spiny ball that projectiles can't hurt.
+    Pufferfish,
+    /// Rams other fish with its bill when swimming at them fast.
+    Swordfish,
+    /// Turns on the spot and squirts ink to hide in.
+    Octopus,
+    /// Fast but fragile.  Shocks everyone close by.
+    Eel,
+}
+
+/// The trick a species can pull off with its ability key.
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum Ability {
+    Inflate,
+    Ink,
+    Shock,
+}
+
+pub(super) struct SpeciesStats {
+    /// Multipliers on the classic fish's top speed, acceleration, turning, hit points and mass.
+    pub(super) speed: f32,
+    pub(super) acceleration: f32,
+    pub(super) turning: f32,
+    pub(super) hp: f32,
+    pub(super) mass: f32,
+    /// Damage dealt by swimming head first into another fish, if any.
+    pub(super) ram_damage: u32,
+    pub(super) ability: Option<Ability>,
+    pub(super) model: ModelDefinition,
+}
+
+impl Species {
+    pub const ALL: [Species; 5] = [
+        Species::Classic,
+        Species::Pufferfish,
+        Species::Swordfish,
+        Species::Octopus,
+        Species::Eel,
+    ];
+
+    /// How big around a fish of this species is compared to a classic one, for bumping into
+    /// things.
+    pub fn collision_scale(self, inflated: bool) -> f32 {
+        let model = self.stats().model;
+        let body = if inflated {
+            model.body.x * INFLATE_SCALE
+        } else {
+            model.body.x
+        };
+
+        (model.head.x.max(body) + model.length) / 2.0
+    }
+
+    pub(super) fn stats(self) -> SpeciesStats {
+        match self {
+            Species::Classic => SpeciesStats {
+                speed: 1.0,
+                acceleration: 1.0,
+                turning: 1.0,
+                hp: 1.0,
+                mass: 1.0,
+                ram_damage: 0,
+                ability: None,
+                model: ModelDefinition::default(),
+            },
+            Species::Pufferfish => SpeciesStats {
+                speed: 0.85,
+                acceleration: 0.9,
+                turning: 1.0,
+                hp: 1.2,
+                mass: 1.5,
+                ram_damage: 0,
+                ability: Some(Ability::Inflate),
+                model: ModelDefinition {
+                    head: Vec2::new(1.3, 1.2),
+                    body: Vec2::new(1.5, 1.3),
+                    tail: Vec2::new(0.8, 0.7),
+                    fins: Vec2::new(0.8, 0.8),
+                    length: 0.8,
+                    bill: false,
+                },
+            },
+            Species::Swordfish => SpeciesStats {
+                speed: 1.1,
+                acceleration: 1.0,
+                turning: 0.8,
+                hp: 1.0,
+                mass: 1.2,
+                ram_damage: 2,
+                ability: None,
+                model: ModelDefinition {
+                    head: Vec2::new(0.9, 1.1),
+                    body: Vec2::new(0.9, 1.2),
+                    tail: Vec2::new(1.1, 1.1),
+                    fins: Vec2::ONE,
+                    length: 1.15,
+                    bill: true,
+                },
+            },
+            Species::Octopus => SpeciesStats {
+                speed: 0.9,
+                acceleration: 1.1,
+                turning: 1.3,
+                hp: 1.0,
+                mass: 1.0,
+                ram_damage: 0,
+                ability: Some(Ability::Ink),
+                model: ModelDefinition {
+                    head: Vec2::new(1.3, 1.4),
+                    body: Vec2::new(0.9, 0.8),
+                    tail: Vec2::new(0.7, 0.9),
+                    fins: Vec2::new(1.3, 1.5),
+                    length: 0.9,
+                    bill: false,
+                },
+            },
+            Species::Eel => SpeciesStats {
+                speed: 1.3,
+                acceleration: 1.2,
+                turning: 1.1,
+                hp: 0.6,
+                mass: 0.7,
+                ram_damage: 0,
+                ability: Some(Ability::Shock),
+                model: ModelDefinition {
+                    head: Vec2::new(0.7, 0.9),
+                    body: Vec2::new(0.6, 1.5),
+                    tail: Vec2::new(0.6, 1.6),
+                    fins: Vec2::new(0.5, 0.5),
+                    length: 1.5,
+                    bill: false,
+                },
+            },
+        }
+    }
+}
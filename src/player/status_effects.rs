@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::core_components::{Dead, HitPoints, KilledBy};
+
+use super::Player;
+
+/// How much each slow takes off a fish's speed.
+const SLOW_MULTIPLIER: f32 = 0.7;
+const HASTE_MULTIPLIER: f32 = 1.5;
+/// Seconds between each point of damage from a dose of poison.
+const POISON_TICK_SECS: f32 = 1.0;
+/// How strongly an effect's colour shows through the fish's own.
+const TINT_STRENGTH: f32 = 0.5;
+
+/// Something temporarily wrong, or right, with a fish.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StatusEffect {
+    /// Can't swim, turn, shoot or shield.
+    Stun,
+    Slow,
+    /// Loses a hit point every so often.
+    Poison,
+    /// Projectiles pass straight through.
+    Invulnerability,
+    Haste,
+}
+
+/// What happens when an effect is applied to a fish that already has it.
+enum Stacking {
+    /// The effect lasts for whichever is longer, what's left of the old one or the new one.
+    Refresh,
+    /// The new duration is added onto what's left of the old one.
+    Extend,
+    /// Each application counts separately, up to a limit.  Past it, the one closest to running
+    /// out is replaced.
+    Stack(usize),
+}
+
+impl StatusEffect {
+    fn stacking(self) -> Stacking {
+        match self {
+            StatusEffect::Stun | StatusEffect::Invulnerability => Stacking::Refresh,
+            StatusEffect::Haste => Stacking::Extend,
+            StatusEffect::Slow => Stacking::Stack(3),
+            StatusEffect::Poison => Stacking::Stack(5),
+        }
+    }
+
+    /// How often the effect does something for as long as it lasts, if it does.
+    fn tick_secs(self) -> Option<f32> {
+        match self {
+            StatusEffect::Poison => Some(POISON_TICK_SECS),
+            _ => None,
+        }
+    }
+
+    fn tint(self) -> Option<Color> {
+        match self {
+            StatusEffect::Stun => Some(Color::rgb(1.0, 1.0, 0.4)),
+            StatusEffect::Slow => Some(Color::rgb(0.4, 0.6, 1.0)),
+            StatusEffect::Poison => Some(Color::rgb(0.4, 1.0, 0.3)),
+            StatusEffect::Invulnerability => None,
+            StatusEffect::Haste => Some(Color::rgb(1.0, 0.5, 0.2)),
+        }
+    }
+}
+
+struct ActiveEffect {
+    effect: StatusEffect,
+    duration: Timer,
+    tick: Option<Timer>,
+    /// Whoever inflicted the effect, to take the credit if it kills.
+    source: Option<Entity>,
+}
+
+/// Every effect currently on a fish, most recently applied last.
+#[derive(Component, Default)]
+pub struct StatusEffects(Vec<ActiveEffect>);
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect, secs: f32) {
+        self.apply_with_source(effect, secs, None);
+    }
+
+    /// Applies an effect on behalf of whoever caused it.
+    pub fn apply_from(&mut self, effect: StatusEffect, secs: f32, source: Entity) {
+        self.apply_with_source(effect, secs, Some(source));
+    }
+
+    fn apply_with_source(&mut self, effect: StatusEffect, secs: f32, source: Option<Entity>) {
+        let fresh = ActiveEffect {
+            effect,
+            duration: Timer::from_seconds(secs, false),
+            tick: effect.tick_secs().map(|t| Timer::from_seconds(t, true)),
+            source,
+        };
+
+        let existing = self.0.iter_mut().filter(|a| a.effect == effect);
+
+        match effect.stacking() {
+            Stacking::Refresh => {
+                if let Some(active) = existing.last() {
+                    let remaining = active.duration.duration() - active.duration.elapsed();
+                    if remaining < fresh.duration.duration() {
+                        *active = fresh;
+                    }
+                    return;
+                }
+            }
+            Stacking::Extend => {
+                if let Some(active) = existing.last() {
+                    let duration = active.duration.duration() + Duration::from_secs_f32(secs);
+                    active.duration.set_duration(duration);
+                    return;
+                }
+            }
+            Stacking::Stack(limit) => {
+                let stacks = existing.count();
+                if stacks >= limit {
+                    let oldest = self
+                        .0
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, a)| a.effect == effect)
+                        .min_by_key(|(_, a)| a.duration.duration() - a.duration.elapsed())
+                        .map(|(i, _)| i);
+
+                    if let Some(oldest) = oldest {
+                        self.0.remove(oldest);
+                    }
+                }
+            }
+        }
+
+        self.0.push(fresh);
+    }
+
+    pub fn has(&self, effect: StatusEffect) -> bool {
+        self.stacks(effect) > 0
+    }
+
+    pub fn stacks(&self, effect: StatusEffect) -> usize {
+        self.0.iter().filter(|a| a.effect == effect).count()
+    }
+
+    /// Whether the fish is in any state to swim, shoot or shield.
+    pub fn can_act(&self) -> bool {
+        !self.has(StatusEffect::Stun)
+    }
+
+    /// How much faster or slower than usual the fish swims.
+    pub fn speed_multiplier(&self) -> f32 {
+        let haste = if self.has(StatusEffect::Haste) {
+            HASTE_MULTIPLIER
+        } else {
+            1.0
+        };
+
+        SLOW_MULTIPLIER.powi(self.stacks(StatusEffect::Slow) as i32) * haste
+    }
+
+    /// The colour of whichever visible effect was applied most recently.
+    fn tint(&self) -> Option<Color> {
+        self.0.iter().rev().find_map(|a| a.effect.tint())
+    }
+}
+
+pub(super) fn update_status_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &mut StatusEffects, &mut HitPoints), With<Player>>,
+) {
+    for (player, mut status_effects, mut hp) in players.iter_mut() {
+        for active in status_effects.0.iter_mut() {
+            active.duration.tick(time.delta());
+
+            let ticks = active
+                .tick
+                .as_mut()
+                .map_or(0, |t| t.tick(time.delta()).times_finished());
+
+            for _ in 0..ticks {
+                match active.effect {
+                    StatusEffect::Poison if hp.0 > 0 => {
+                        hp.0 -= 1;
+                        debug!("{:?} hp: {}", player, hp.0);
+
+                        if hp.0 == 0 {
+                            commands.entity(player).insert(Dead);
+
+                            if let Some(source) = active.source {
+                                commands.entity(player).insert(KilledBy(source));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        status_effects.0.retain(|a| !a.duration.finished());
+    }
+}
+
+/// Blends the colour of a fish's most recent visible effect into its body parts.
+pub(super) fn status_tint(status_effects: &StatusEffects, color: Color) -> Color {
+    match status_effects.tint() {
+        Some(tint) => Color::rgba(
+            color.r() + (tint.r() - color.r()) * TINT_STRENGTH,
+            color.g() + (tint.g() - color.g()) * TINT_STRENGTH,
+            color.b() + (tint.b() - color.b()) * TINT_STRENGTH,
+            color.a(),
+        ),
+        None => color,
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use super::Team;
+
+/// Seconds a charged shot takes to reach full power.
+pub(super) const CHARGE_SECS: f32 = 1.5;
+/// How much more a fully charged shot costs, hurts and weighs than an uncharged one.
+pub(super) const CHARGE_MAX_MULTIPLIER: f32 = 3.0;
+
+#[derive(Clone, Component, Copy, Debug, PartialEq)]
+pub enum Weapon {
+    Standard,
+    Charged,
+    Spread,
+    Torpedo,
+    Needle,
+}
+
+pub(super) struct WeaponStats {
+    pub(super) energy_cost: f32,
+    pub(super) speed: f32,
+    pub(super) damage: u32,
+    pub(super) scale: f32,
+    pub(super) color: Color,
+    pub(super) texture: &'static str,
+    /// Angles, relative to the fish's heading, of each projectile fired in one shot.
+    pub(super) angles: &'static [f32],
+    /// How far the projectile can turn towards its target each frame, if at all.
+    pub(super) homing: Option<f32>,
+}
+
+impl Weapon {
+    pub const ALL: [Weapon; 5] = [
+        Weapon::Standard,
+        Weapon::Charged,
+        Weapon::Spread,
+        Weapon::Torpedo,
+        Weapon::Needle,
+    ];
+
+    pub fn color(self) -> Color {
+        self.stats().color
+    }
+
+    pub fn texture(self) -> &'static str {
+        self.stats().texture
+    }
+
+    pub(super) fn stats(self) -> WeaponStats {
+        match self {
+            Weapon::Standard => WeaponStats {
+                energy_cost: 1.0,
+                speed: 12.0,
+                damage: 1,
+                scale: 0.2,
+                color: Color::WHITE,
+                texture: "images/projectile.png",
+                angles: &[0.0],
+                homing: None,
+            },
+            Weapon::Charged => WeaponStats {
+                energy_cost: 1.0,
+                speed: 10.0,
+                damage: 1,
+                scale: 0.2,
+                color: Color::rgb(1.0, 0.8, 0.5),
+                texture: "images/projectile-charged.png",
+                angles: &[0.0],
+                homing: None,
+            },
+            Weapon::Spread => WeaponStats {
+                energy_cost: 2.0,
+                speed: 11.0,
+                damage: 1,
+                scale: 0.16,
+                color: Color::rgb(0.6, 1.0, 0.6),
+                texture: "images/projectile-pellet.png",
+                angles: &[-0.25, 0.0, 0.25],
+                homing: None,
+            },
+            Weapon::Torpedo => WeaponStats {
+                energy_cost: 2.0,
+                speed: 5.0,
+                damage: 2,
+                scale: 0.3,
+                color: Color::rgb(1.0, 0.5, 0.5),
+                texture: "images/projectile-torpedo.png",
+                angles: &[0.0],
+                homing: Some(0.03),
+            },
+            Weapon::Needle => WeaponStats {
+                energy_cost: 0.5,
+                speed: 22.0,
+                damage: 1,
+                scale: 0.12,
+                color: Color::rgb(0.6, 0.8, 1.0),
+                texture: "images/projectile-needle.png",
+                angles: &[0.0],
+                homing: None,
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+pub(super) struct ChargingShot(pub(super) Timer);
+
+#[derive(Component)]
+pub(super) struct Homing {
+    pub(super) turn_rate: f32,
+    /// The shooter's team, kept so the projectile still knows who not to chase once they're gone.
+    pub(super) team: Team,
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::collision::ArenaBounds;
+use crate::configuration::{Ruleset, LOGICAL_HEIGHT, LOGICAL_WIDTH};
+use crate::State;
+
+pub struct SuddenDeathPlugin;
+
+impl Plugin for SuddenDeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MatchTime::default())
+            .add_system_set(
+                SystemSet::on_enter(State::Game)
+                    .with_system(reset_arena)
+                    .with_system(spawn_darkness),
+            )
+            .add_system_set(
+                SystemSet::on_update(State::Game)
+                    .with_system(shrink_arena.label("shrink_arena").before("physics"))
+                    .with_system(move_darkness.after("shrink_arena")),
+            );
+    }
+}
+
+/// How far the darkness reaches past the edges of the arena, to cover any letterboxing.
+const DARKNESS_OVERSCAN: f32 = 1000.0;
+
+#[derive(Default)]
+struct MatchTime(f32);
+
+#[derive(Clone, Component, Copy)]
+enum Darkness {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+fn reset_arena(mut bounds: ResMut<ArenaBounds>, mut match_time: ResMut<MatchTime>) {
+    *bounds = ArenaBounds::default();
+    match_time.0 = 0.0;
+}
+
+fn spawn_darkness(mut commands: Commands) {
+    for side in [
+        Darkness::Left,
+        Darkness::Right,
+        Darkness::Bottom,
+        Darkness::Top,
+    ] {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.85),
+                    custom_size: Some(Vec2::ZERO),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(side);
+    }
+}
+
+fn shrink_arena(
+    time: Res<Time>,
+    ruleset: Res<Ruleset>,
+    mut match_time: ResMut<MatchTime>,
+    mut bounds: ResMut<ArenaBounds>,
+) {
+    let sudden_death = match &ruleset.sudden_death {
+        Some(sudden_death) => sudden_death,
+        None => return,
+    };
+
+    let before = match_time.0;
+    match_time.0 += time.delta_seconds();
+
+    if match_time.0 < sudden_death.start_secs {
+        return;
+    }
+
+    if before < sudden_death.start_secs {
+        println!("Sudden death!");
+    }
+
+    let t = ((match_time.0 - sudden_death.start_secs) / sudden_death.shrink_secs).min(1.0);
+    let arena = Vec2::new(LOGICAL_WIDTH as f32, LOGICAL_HEIGHT as f32);
+    let margin = arena * (1.0 - sudden_death.final_size) / 2.0 * t;
+
+    bounds.min = margin;
+    bounds.max = arena - margin;
+}
+
+fn move_darkness(
+    bounds: Res<ArenaBounds>,
+    mut darkness: Query<(&Darkness, &mut Sprite, &mut Transform)>,
+) {
+    let arena = Vec2::new(LOGICAL_WIDTH as f32, LOGICAL_HEIGHT as f32);
+    let outer_min = -Vec2::splat(DARKNESS_OVERSCAN);
+    let outer_max = arena + DARKNESS_OVERSCAN;
+
+    for (side, mut sprite, mut transform) in darkness.iter_mut() {
+        let (min, max) = match side {
+            Darkness::Left => (outer_min, Vec2::new(bounds.min.x, outer_max.y)),
+            Darkness::Right => (Vec2::new(bounds.max.x, outer_min.y), outer_max),
+            Darkness::Bottom => (
+                Vec2::new(bounds.min.x, outer_min.y),
+                Vec2::new(bounds.max.x, bounds.min.y),
+            ),
+            Darkness::Top => (
+                Vec2::new(bounds.min.x, bounds.max.y),
+                Vec2::new(bounds.max.x, outer_max.y),
+            ),
+        };
+
+        sprite.custom_size = Some((max - min).max(Vec2::ZERO));
+        transform.translation = ((min + max) / 2.0).extend(5.0);
+    }
+}